@@ -0,0 +1,58 @@
+use std::process::ExitCode;
+
+use letterfront::models::replay::{Replay, ReplayPlayer};
+
+/// plays a replay without a window: `cargo run --example replay -- replays/<seed>.json`
+///
+/// fails if the replay can not be played or plays out differently.
+pub fn main() -> ExitCode {
+    let Some(path) = std::env::args().nth(1) else {
+        println!("usage: replay <replay.json>");
+        return ExitCode::FAILURE;
+    };
+    let replay = match Replay::read(&path) {
+        Ok(replay) => replay,
+        Err(err) => {
+            println!("Could not read the replay {path}: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+    // the replay only plays out the same with the corpus it was recorded with:
+    let corpus = match replay
+        .corpus
+        .load(replay.settings.rules.shortest_word_length())
+    {
+        Ok(corpus) => corpus,
+        Err(err) => {
+            println!(
+                "Could not load the corpus {} of the replay: {err}",
+                replay.corpus.path.display()
+            );
+            return ExitCode::FAILURE;
+        }
+    };
+    let mut player = match ReplayPlayer::new(&replay, corpus) {
+        Ok(player) => player,
+        Err(err) => {
+            println!("{err}");
+            return ExitCode::FAILURE;
+        }
+    };
+    while let Some(result) = player.step() {
+        match result {
+            Ok(step) => println!("{}: {} points", step.mv, step.score),
+            Err(err) => {
+                println!("{err}");
+                println!("{}", player.session.letterfield);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    println!("{}", player.session.letterfield);
+    println!(
+        "Replayed {} moves without divergence, {} points",
+        player.moves_played(),
+        player.session.score.total
+    );
+    ExitCode::SUCCESS
+}
@@ -1,10 +1,28 @@
-use letterfront::models::{array2d::Int2, corpus::Corpus, letterfield::Letterfield};
+use letterfront::models::{
+    array2d::Int2, corpus::Corpus, letter_source::IndependentLetters, letterfield::Letterfield,
+};
+use rand::thread_rng;
 
 pub fn main() {
-    let corpus = Corpus::from_txt_file("assets/english3000.txt", 4).unwrap();
-    let mut letterfield = Letterfield::random(4, 3, &corpus);
+    let corpus = match Corpus::from_txt_file("assets/english3000.txt", 4) {
+        Ok(corpus) => corpus,
+        Err(err) => {
+            println!("Could not load the corpus: {err}");
+            return;
+        }
+    };
+    let mut letters = IndependentLetters::new(&corpus).unwrap();
+    let mut letterfield = match Letterfield::random(4, 3, &mut letters, &mut thread_rng()) {
+        Ok(letterfield) => letterfield,
+        Err(err) => {
+            println!("Could not create the letterfield: {err}");
+            return;
+        }
+    };
     println!("{}", letterfield.to_detail_string());
-    letterfield.move_letter(Int2 { x: 1, y: 2 }, Int2 { x: 1, y: 0 });
+    if let Err(err) = letterfield.move_letter(Int2 { x: 1, y: 2 }, Int2 { x: 1, y: 0 }) {
+        println!("{err}");
+    }
     println!("///");
     println!("{}", letterfield.to_detail_string())
 }
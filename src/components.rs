@@ -1,16 +1,18 @@
 use std::f32::consts::PI;
 
 use bevy::{prelude::*, sprite::COLOR_MATERIAL_SHADER_HANDLE, text::TextStyle};
-use rand::random;
+use rand::Rng;
 
 use crate::{
-    constants::FALLING_SPEED_PER_10_BLOCKS, models::array2d::Int2, utils::AnimationDriver,
+    constants::FALLING_SPEED_PER_10_BLOCKS,
+    models::{array2d::Int2, letter::Letter},
+    utils::AnimationDriver,
 };
 
 #[derive(Debug, Clone, Component)]
 pub struct LetterTile {
     pub id: u32,
-    pub character: char,
+    pub character: Letter,
     pub pos: Int2,
 }
 
@@ -38,6 +40,12 @@ impl AnimationDriver for FallingLetter {
     }
 }
 
+/// floating text over a resolved match with its points, rarity tier and chain.
+#[derive(Debug, Clone, Component, Default)]
+pub struct ScorePopup {
+    pub time: f32,
+}
+
 #[derive(Debug, Clone, Component)]
 pub struct FadingLetter {
     pub time: f32,
@@ -46,12 +54,12 @@ pub struct FadingLetter {
 }
 
 impl FadingLetter {
-    pub fn new() -> Self {
+    pub fn new(rng: &mut impl Rng) -> Self {
         Self {
             time: 0.0,
             explode_direction: Vec2 {
-                x: random::<f32>().abs(),
-                y: random::<f32>() * 2.0,
+                x: rng.gen::<f32>().abs(),
+                y: rng.gen::<f32>() * 2.0,
             } * 200.0,
             down_speed: 0.0,
         }
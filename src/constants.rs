@@ -1,6 +1,6 @@
 use std::time::Duration;
 
-use crate::models::array2d::Int2;
+use crate::models::{array2d::Int2, letter_source::LetterSourceMode};
 
 pub const TILE_SIZE: f32 = 64.0;
 pub const LETTERFIELD_SIZE: Int2 = Int2 { x: 20, y: 15 };
@@ -10,5 +10,18 @@ pub const TILE_GAP_FACTOR: f32 = 1.1;
 pub const RESOLVE_DURATION: Duration = Duration::from_millis(1000);
 pub const GRAVITY_ACCELERATION: f32 = 9.81;
 pub const MIN_WORD_LENGTH: usize = 5;
+pub const SAVEGAME_PATH: &str = "savegame.json";
+/// the word list of the default `CorpusSource`
+pub const CORPUS_PATH: &str = "assets/3esl.txt";
+/// the common and the uncommon words of `CORPUS_PATH`, all other words are rare
+pub const RARITY_TIER_PATHS: (&str, &str) = ("assets/english3000.txt", "assets/english10000.txt");
+/// every game is written to `<seed>.json` in here
+pub const REPLAY_DIR: &str = "replays";
+/// time between two moves when playing back a replay
+pub const REPLAY_MOVE_INTERVAL: Duration = Duration::from_millis(1500);
+/// `LetterSourceMode::Bigram` makes cascades more (wordiness > 0) or less (wordiness < 0) likely.
+pub const LETTER_SOURCE: LetterSourceMode = LetterSourceMode::TileBag(200);
+/// tiles that hold several letters, a lone Q is hard to use:
+pub const MULTI_LETTER_TILES: &[&str] = &["QU"];
 /// in seconds:
 pub const FALLING_SPEED_PER_10_BLOCKS: f32 = 1.0;
@@ -1,6 +1,27 @@
 use bevy::{prelude::*, text::TextStyle};
+
+use crate::models::score::MatchScore;
 pub struct EventsPlugin;
 
 impl Plugin for EventsPlugin {
-    fn build(&self, app: &mut bevy::prelude::App) {}
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.add_event::<MatchScoredEvent>()
+            .add_event::<HistoryEvent>()
+            .add_event::<NewGameEvent>();
+    }
 }
+
+/// sent for every match that is resolved, carries the cascade depth for chain feedback.
+#[derive(Debug, Clone, Event)]
+pub struct MatchScoredEvent(pub MatchScore);
+
+/// takes back the last move or does the last undone move again, see `MoveHistoryResource`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Event)]
+pub enum HistoryEvent {
+    Undo,
+    Redo,
+}
+
+/// ends the current game and starts a new one with the same settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Event)]
+pub struct NewGameEvent;
@@ -1,8 +1,18 @@
+#[cfg(feature = "bevy")]
 pub mod components;
 pub mod constants;
+#[cfg(feature = "bevy")]
 pub mod events;
 pub mod models;
+#[cfg(feature = "bevy")]
+pub mod plugin;
+#[cfg(feature = "bevy")]
 pub mod resources;
+#[cfg(feature = "bevy")]
+pub mod save;
+#[cfg(feature = "bevy")]
 pub mod state;
+#[cfg(feature = "bevy")]
 pub mod systems;
+#[cfg(feature = "bevy")]
 pub mod utils;
@@ -9,7 +9,6 @@ use bevy::{
 };
 
 use letterfront::models::{array2d::Int2, corpus::Corpus, letterfield::Letterfield};
-use letterfront::{events::EventsPlugin, resources::WordMatchesResource};
 use rand::random;
 
 use bevy::{
@@ -22,13 +21,17 @@ use bevy::{
     winit::WinitSettings,
 };
 
-use bevy_egui::{egui, EguiContexts, EguiPlugin};
 use letterfront::constants::*;
-use letterfront::resources::{LetterfieldResource, ResourcesPlugin};
-use letterfront::state::{IngameState, StateSystemsPlugin};
-use letterfront::systems::{input::InputSystemsPlugin, setup::SetupSystemsPlugin};
+use letterfront::plugin::{LetterfrontConfig, LetterfrontPlugin};
 
 fn main() {
+    let config = LetterfrontConfig {
+        seed: std::env::var("LETTERFRONT_SEED")
+            .ok()
+            .and_then(|seed| seed.parse().ok()),
+        replay_path: std::env::var_os("LETTERFRONT_REPLAY").map(Into::into),
+        ..default()
+    };
     App::new()
         .insert_resource(Msaa::Sample8)
         .add_plugins(DefaultPlugins.set(WindowPlugin {
@@ -38,28 +41,8 @@ fn main() {
             }),
             ..default()
         }))
-        .add_state::<IngameState>()
-        .add_plugins(EventsPlugin)
-        .add_plugins(ResourcesPlugin)
-        .add_plugins(StateSystemsPlugin)
-        .add_plugins(EguiPlugin)
-        .add_plugins(SetupSystemsPlugin)
-        .add_plugins(InputSystemsPlugin)
-        .add_systems(Update, egui_debug)
+        .add_plugins(LetterfrontPlugin { config })
         .add_systems(Update, bevy::window::close_on_esc)
         // .add_systems(Update, cursor_grab_system)
         .run();
 }
-
-fn egui_debug(
-    mut contexts: EguiContexts,
-    ingame_state: Res<State<IngameState>>,
-    letterfield: Res<LetterfieldResource>,
-    word_matches: Res<WordMatchesResource>,
-) {
-    egui::Window::new("Info").show(contexts.ctx_mut(), |ui| {
-        ui.label(format!("IngameState: {:?}", ingame_state));
-        ui.label(format!("Letterfield: \n {}", &letterfield.0));
-        ui.label(format!("Matches: \n {:?}", &word_matches));
-    });
-}
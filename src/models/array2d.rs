@@ -1,7 +1,12 @@
-use std::ops::{Index, IndexMut};
+use std::{
+    fmt::Display,
+    ops::{Index, IndexMut},
+};
+
+use serde::{Deserialize, Serialize};
 
 // x, y
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Int2 {
     pub x: usize,
     pub y: usize,
@@ -25,7 +30,7 @@ impl From<(usize, usize)> for Int2 {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Array2D<T> {
     pub width: usize,
     pub height: usize,
@@ -62,10 +67,10 @@ impl<T: Clone> Array2D<T> {
     /// only diags that are 3 or longer
     pub fn diags(&self, min_len: usize) -> Vec<(Vec<T>, Int2)> {
         let mut diag_start_points: Vec<Int2> = vec![];
-        for i in 0..(self.height + 1 - min_len) {
+        for i in 0..(self.height + 1).saturating_sub(min_len) {
             diag_start_points.push(Int2 { x: 0, y: i });
         }
-        for i in 1..(self.width + 1 - min_len) {
+        for i in 1..(self.width + 1).saturating_sub(min_len) {
             diag_start_points.push(Int2 { x: i, y: 0 });
         }
         diag_start_points
@@ -85,6 +90,21 @@ impl<T: Clone> Array2D<T> {
             .collect()
     }
 
+    /// bottom-left to top-right diags, only diags that are min_len or longer
+    pub fn anti_diags(&self, min_len: usize) -> Vec<(Vec<T>, Int2)> {
+        self.anti_diag_start_points(min_len)
+            .into_iter()
+            .map(|start_point| {
+                let Int2 { x, y } = start_point;
+                let travel = (self.width - x).min(y + 1);
+                let diag: Vec<T> = (0..travel)
+                    .map(|i| self[Int2 { x: x + i, y: y - i }].clone())
+                    .collect();
+                (diag, start_point)
+            })
+            .collect()
+    }
+
     pub fn iter(&self) -> Array2DIter<'_, T> {
         Array2DIter {
             col: 0,
@@ -94,6 +114,23 @@ impl<T: Clone> Array2D<T> {
     }
 }
 
+impl<T> Array2D<T> {
+    /// start points of the bottom-left to top-right diags: the left column and the bottom row.
+    fn anti_diag_start_points(&self, min_len: usize) -> Vec<Int2> {
+        let mut diag_start_points: Vec<Int2> = vec![];
+        for i in (min_len.max(1) - 1)..self.height {
+            diag_start_points.push(Int2 { x: 0, y: i });
+        }
+        for i in 1..(self.width + 1).saturating_sub(min_len) {
+            diag_start_points.push(Int2 {
+                x: i,
+                y: self.height - 1,
+            });
+        }
+        diag_start_points
+    }
+}
+
 impl<A: Clone, B: Clone> Array2D<(A, B)> {
     pub fn rows_2(&self) -> Vec<(Vec<B>, Int2)> {
         (0..self.height)
@@ -122,10 +159,10 @@ impl<A: Clone, B: Clone> Array2D<(A, B)> {
 
     pub fn diags_2(&self, min_len: usize) -> Vec<(Vec<B>, Int2)> {
         let mut diag_start_points: Vec<Int2> = vec![];
-        for i in 0..(self.height + 1 - min_len) {
+        for i in 0..(self.height + 1).saturating_sub(min_len) {
             diag_start_points.push(Int2 { x: 0, y: i });
         }
-        for i in 1..(self.width + 1 - min_len) {
+        for i in 1..(self.width + 1).saturating_sub(min_len) {
             diag_start_points.push(Int2 { x: i, y: 0 });
         }
         diag_start_points
@@ -144,16 +181,69 @@ impl<A: Clone, B: Clone> Array2D<(A, B)> {
             })
             .collect()
     }
+
+    /// bottom-left to top-right diags, only diags that are min_len or longer
+    pub fn anti_diags_2(&self, min_len: usize) -> Vec<(Vec<B>, Int2)> {
+        self.anti_diag_start_points(min_len)
+            .into_iter()
+            .map(|start_point| {
+                let Int2 { x, y } = start_point;
+                let travel = (self.width - x).min(y + 1);
+                let diag: Vec<B> = (0..travel)
+                    .map(|i| self[Int2 { x: x + i, y: y - i }].1.clone())
+                    .collect();
+                (diag, start_point)
+            })
+            .collect()
+    }
 }
 
+/// why nested vecs are not an `Array2D`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Array2DError {
+    /// no lines or empty lines
+    Empty,
+    /// the line at `index` has `len` elements, but the first one has `expected`
+    RaggedLine {
+        index: usize,
+        len: usize,
+        expected: usize,
+    },
+}
+
+impl Display for Array2DError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Array2DError::Empty => write!(f, "no elements"),
+            Array2DError::RaggedLine {
+                index,
+                len,
+                expected,
+            } => write!(
+                f,
+                "line {index} has {len} elements, but the first line has {expected}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Array2DError {}
+
 impl<T> TryFrom<Vec<Vec<T>>> for Array2D<T> {
-    type Error = ();
+    type Error = Array2DError;
 
     fn try_from(cols: Vec<Vec<T>>) -> Result<Self, Self::Error> {
         let width = cols.len();
-        let height = cols[0].len();
-        if cols.iter().any(|c| c.len() != height) {
-            return Err(());
+        let height = cols.first().map(|c| c.len()).unwrap_or(0);
+        if height == 0 {
+            return Err(Array2DError::Empty);
+        }
+        if let Some((index, c)) = cols.iter().enumerate().find(|(_, c)| c.len() != height) {
+            return Err(Array2DError::RaggedLine {
+                index,
+                len: c.len(),
+                expected: height,
+            });
         }
         Ok(Array2D {
             width,
@@ -221,7 +311,7 @@ impl<'a, T: Clone> Iterator for Array2DIter<'a, T> {
 
 #[cfg(test)]
 mod test {
-    use crate::models::array2d::Array2D;
+    use crate::models::array2d::{Array2D, Array2DError, Int2};
 
     fn number_array(width: usize, height: usize) -> Array2D<i32> {
         let mut c = 0;
@@ -242,7 +332,15 @@ mod test {
     #[test]
     fn array2d_construction() {
         let broken_cols = vec![vec![0, 1], vec![0, 1, 2], vec![0, 1, 2]];
-        assert!(Array2D::try_from(broken_cols).is_err());
+        assert_eq!(
+            Array2D::try_from(broken_cols),
+            Err(Array2DError::RaggedLine {
+                index: 1,
+                len: 3,
+                expected: 2
+            })
+        );
+        assert_eq!(Array2D::<i32>::try_from(vec![]), Err(Array2DError::Empty));
 
         let cols = vec![vec![0, 1, 2], vec![0, 1, 2], vec![0, 1, 2]];
         let arr = Array2D::try_from(cols).unwrap();
@@ -262,8 +360,30 @@ mod test {
         // _ _ X A B C D
         assert_eq!(arr.diags(3).len(), 6);
         assert_eq!(arr.diags(4).len(), 4);
+        // _ _ X A B C D
+        // _ X A B C D E
+        // X A B C D E _
+        // A B C D E _ _
+        assert_eq!(arr.anti_diags(3).len(), 6);
+        assert_eq!(arr.anti_diags(4).len(), 4);
+        assert_eq!(
+            arr.anti_diags(4)[0],
+            (vec![4, 7, 10, 13], Int2 { x: 0, y: 3 })
+        );
 
         assert_eq!(arr.rows().len(), 4);
         assert_eq!(arr.cols().len(), 7);
     }
+
+    #[test]
+    fn array2d_diags_longer_than_the_board() {
+        let arr = number_array(3, 3);
+        assert!(arr.diags(5).is_empty());
+        assert!(arr.anti_diags(5).is_empty());
+        assert_eq!(arr.diags(4).len(), 0);
+        assert_eq!(arr.diags(3).len(), 1);
+
+        let pairs = Array2D::try_from(vec![vec![(0, 'a'); 3]; 2]).unwrap();
+        assert!(pairs.diags_2(5).is_empty());
+    }
 }
@@ -1,14 +1,107 @@
 use std::{
-    collections::{HashMap, HashSet},
-    path::Path,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fmt::Display,
+    path::{Path, PathBuf},
 };
 
-use rand::{thread_rng, Rng};
+use serde::{Deserialize, Serialize};
+
+use super::{hunspell, letter::Letter, rules::OverlapPolicy, text, trie::Trie};
 
 #[derive(Debug, Clone)]
 pub struct Corpus {
     pub words: HashSet<String>,
-    pub char_probabilities: HashMap<char, f64>,
+    /// frequency rank of each word, 1 for the most frequent one. empty if the words were not ranked.
+    ranks: HashMap<String, usize>,
+    /// how likely each tile is, multi-letter tiles count instead of their single letters.
+    /// ordered by letter, so that sampling from it only depends on the rng, see `IndependentLetters`.
+    pub letter_probabilities: BTreeMap<Letter, f64>,
+    /// the same words as `words`, built once at load time for fast prefix lookups in `line_search`.
+    trie: Trie,
+    rarity_tiers: Option<RarityTiers>,
+    letter_weighting: LetterWeighting,
+    /// the tiles with several letters like QU, see `with_multi_letter_tiles`.
+    multi_letter_tiles: BTreeSet<Letter>,
+}
+
+/// why a corpus could not be loaded.
+#[derive(Debug)]
+pub enum CorpusError {
+    /// a file could not be read
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// a hunspell dictionary or affix file is broken
+    Hunspell {
+        path: PathBuf,
+        source: hunspell::HunspellError,
+    },
+    /// no words (and therefore no letters) are left after filtering
+    Empty,
+}
+
+impl Display for CorpusError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CorpusError::Io { path, source } => {
+                write!(f, "could not read {}: {source}", path.display())
+            }
+            CorpusError::Hunspell { path, source } => {
+                write!(f, "invalid hunspell file {}: {source}", path.display())
+            }
+            CorpusError::Empty => write!(f, "the corpus has no words"),
+        }
+    }
+}
+
+impl std::error::Error for CorpusError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CorpusError::Io { source, .. } => Some(source),
+            CorpusError::Hunspell { source, .. } => Some(source),
+            CorpusError::Empty => None,
+        }
+    }
+}
+
+fn read_file(path: impl AsRef<Path>) -> Result<Vec<u8>, CorpusError> {
+    std::fs::read(path.as_ref()).map_err(|source| CorpusError::Io {
+        path: path.as_ref().to_path_buf(),
+        source,
+    })
+}
+
+fn read_text_file(path: impl AsRef<Path>) -> Result<String, CorpusError> {
+    std::fs::read_to_string(path.as_ref()).map_err(|source| CorpusError::Io {
+        path: path.as_ref().to_path_buf(),
+        source,
+    })
+}
+
+/// how rare a word is, rarer words pay out more points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Rarity {
+    Common,
+    Uncommon,
+    Rare,
+}
+
+/// the words that are common and uncommon, all other words of a corpus are rare.
+#[derive(Debug, Clone)]
+struct RarityTiers {
+    common: HashSet<String>,
+    uncommon: HashSet<String>,
+}
+
+/// how much the letters of each word count for the `letter_probabilities` of a corpus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LetterWeighting {
+    /// every word counts the same
+    Uniform,
+    /// a word counts 1 / its frequency rank, like words in running text according to Zipf's law.
+    /// needs ranked words, see `Corpus::from_ranked_words`.
+    Zipf,
 }
 
 impl Corpus {
@@ -16,118 +109,644 @@ impl Corpus {
     where
         String: From<T>,
     {
-        let mut char_counts: HashMap<char, usize> = HashMap::new();
-        let mut total_count: usize = 0;
+        Self::from_optionally_ranked_words(
+            words.into_iter().map(|w| (String::from(w), None)),
+            LetterWeighting::Uniform,
+        )
+    }
+
+    /// words ordered by frequency, the most frequent one first.
+    pub fn from_ranked_words<T>(
+        words: impl IntoIterator<Item = T>,
+        weighting: LetterWeighting,
+    ) -> Self
+    where
+        String: From<T>,
+    {
+        Self::from_optionally_ranked_words(
+            words
+                .into_iter()
+                .enumerate()
+                .map(|(i, w)| (String::from(w), Some(i + 1))),
+            weighting,
+        )
+    }
+
+    fn from_optionally_ranked_words(
+        words: impl Iterator<Item = (String, Option<usize>)>,
+        weighting: LetterWeighting,
+    ) -> Self {
+        let mut unique_words: Vec<(String, Option<usize>)> = vec![];
+        let mut ranks: HashMap<String, usize> = HashMap::new();
+        let mut seen: HashSet<String> = HashSet::new();
+        for (s, rank) in words {
+            // only words with at least 2 letters and no special characters (needs improvement).
+            // the minimum length of a match is decided by the RuleSet.
+            if s.contains('\'') || s.contains('-') || text::grapheme_len(&s) < 2 {
+                continue;
+            }
+            let s = text::uppercase(&s);
+            if !text::fits_on_tiles(&s) {
+                continue;
+            }
+            if !seen.insert(s.clone()) {
+                // the same word in other casing, it keeps its first (highest) rank
+                continue;
+            }
+            if let Some(rank) = rank {
+                ranks.insert(s.clone(), rank);
+            }
+            unique_words.push((s, rank));
+        }
 
-        let words: HashSet<String> = words
+        let words: HashSet<String> = unique_words.into_iter().map(|(w, _)| w).collect();
+        let trie = words.iter().collect();
+
+        let mut corpus = Self {
+            words,
+            ranks,
+            letter_probabilities: BTreeMap::new(),
+            trie,
+            rarity_tiers: None,
+            letter_weighting: weighting,
+            multi_letter_tiles: BTreeSet::new(),
+        };
+        corpus.letter_probabilities = corpus.compute_letter_probabilities();
+        corpus
+    }
+
+    fn compute_letter_probabilities(&self) -> BTreeMap<Letter, f64> {
+        // in a fixed order, such that summing up the letter weights is deterministic:
+        let mut words: Vec<&String> = self.words.iter().collect();
+        words.sort();
+
+        let mut letter_weights: BTreeMap<Letter, f64> = BTreeMap::new();
+        let mut total_weight: f64 = 0.0;
+        for w in words {
+            let weight = match (self.letter_weighting, self.rank(w)) {
+                (LetterWeighting::Zipf, Some(rank)) => 1.0 / rank as f64,
+                _ => 1.0,
+            };
+            for letter in self.split_into_tiles(w) {
+                *letter_weights.entry(letter).or_insert(0.0) += weight;
+                total_weight += weight;
+            }
+        }
+
+        letter_weights
             .into_iter()
-            .filter_map(|s| {
-                let s = String::from(s);
-                // only words with at least 3 letters and no special characters (needs improvement):
-                if s.contains('\'') || s.contains('-') {
-                    None
-                } else if s.len() >= 3 {
-                    Some(s.to_uppercase())
-                } else {
-                    None
-                }
-            })
+            .map(|(k, v)| (k, v / total_weight))
+            .collect()
+    }
+
+    /// tiles like QU or TH that hold several letters (in upper case, like the corpus words).
+    ///
+    /// wherever a word contains them, they replace their single letters in the `letter_probabilities`,
+    /// so e.g. a Q tile only comes up for words with a Q that is not followed by a U.
+    pub fn with_multi_letter_tiles(mut self, tiles: impl IntoIterator<Item = Letter>) -> Self {
+        self.multi_letter_tiles = tiles
+            .into_iter()
+            .filter(|tile| tile.is_sequence())
             .collect();
-        for w in words.iter() {
-            for c in w.chars() {
-                let entry = char_counts.entry(c).or_insert(0);
-                *entry += 1;
-                total_count += 1;
-            }
+        self.letter_probabilities = self.compute_letter_probabilities();
+        self
+    }
+
+    /// the tiles a word is made of, the longest multi-letter tile is taken wherever one fits.
+    pub fn split_into_tiles(&self, word: &str) -> Vec<Letter> {
+        let chars: Vec<char> = word.chars().collect();
+        let mut tiles: Vec<Letter> = vec![];
+        let mut i = 0;
+        while i < chars.len() {
+            let tile = self
+                .multi_letter_tiles
+                .iter()
+                .filter(|tile| tile.chars().zip(&chars[i..]).all(|(a, b)| a == *b))
+                .filter(|tile| tile.chars().count() <= chars.len() - i)
+                .max_by_key(|tile| tile.chars().count())
+                .copied()
+                .unwrap_or(Letter::from(chars[i]));
+            i += tile.chars().count();
+            tiles.push(tile);
         }
+        tiles
+    }
 
-        let char_probabilities: HashMap<char, f64> = char_counts
+    /// the same corpus with the diacritics removed from all words, e.g. CAFÉ becomes CAFE.
+    ///
+    /// words that only differ in their diacritics become one word, with the best rank of them.
+    pub fn with_folded_diacritics(self) -> Self {
+        let mut words: Vec<(String, Option<usize>)> = self
+            .words
+            .iter()
+            .map(|w| (text::fold_diacritics(w), self.rank(w)))
+            .collect();
+        // ranked words first and by rank, such that the folded words keep the best one:
+        words.sort_by(|(a, a_rank), (b, b_rank)| {
+            (a_rank.is_none(), a_rank, a).cmp(&(b_rank.is_none(), b_rank, b))
+        });
+        let mut folded =
+            Self::from_optionally_ranked_words(words.into_iter(), self.letter_weighting)
+                .with_multi_letter_tiles(self.multi_letter_tiles);
+        let fold_all = |words: HashSet<String>| -> HashSet<String> {
+            words.iter().map(|w| text::fold_diacritics(w)).collect()
+        };
+        folded.rarity_tiers = self.rarity_tiers.map(|tiers| RarityTiers {
+            common: fold_all(tiers.common),
+            uncommon: fold_all(tiers.uncommon),
+        });
+        folded
+    }
+
+    /// words in `common` are common, words only in `uncommon` are uncommon and all others are rare.
+    pub fn with_rarity_tiers<C, U>(
+        mut self,
+        common: impl IntoIterator<Item = C>,
+        uncommon: impl IntoIterator<Item = U>,
+    ) -> Self
+    where
+        String: From<C> + From<U>,
+    {
+        let common: HashSet<String> = Corpus::from_words(common).words;
+        let uncommon: HashSet<String> = Corpus::from_words(uncommon)
+            .words
             .into_iter()
-            .map(|(k, v)| (k, v as f64 / total_count as f64))
+            .filter(|w| !common.contains(w))
             .collect();
+        self.rarity_tiers = Some(RarityTiers { common, uncommon });
+        self
+    }
 
-        Self {
-            words,
-            char_probabilities,
+    /// like `with_rarity_tiers`, with one word per line in each file.
+    pub fn with_rarity_tier_files(
+        self,
+        common_path: impl AsRef<Path>,
+        uncommon_path: impl AsRef<Path>,
+    ) -> Result<Self, CorpusError> {
+        let common = read_text_file(common_path)?;
+        let uncommon = read_text_file(uncommon_path)?;
+        Ok(self.with_rarity_tiers(
+            common.lines().map(str::trim),
+            uncommon.lines().map(str::trim),
+        ))
+    }
+
+    /// the rarity of a word (in upper case, like the corpus words). without rarity tiers every word is common.
+    pub fn rarity(&self, word: &str) -> Rarity {
+        match &self.rarity_tiers {
+            None => Rarity::Common,
+            Some(tiers) if tiers.common.contains(word) => Rarity::Common,
+            Some(tiers) if tiers.uncommon.contains(word) => Rarity::Uncommon,
+            Some(_) => Rarity::Rare,
         }
     }
 
-    pub fn from_txt_file(path: impl AsRef<Path>, min_word_len: usize) -> Result<Self, ()> {
-        let content = std::fs::read_to_string(path).map_err(|_| ())?;
+    pub fn from_txt_file(path: impl AsRef<Path>, min_word_len: usize) -> Result<Self, CorpusError> {
+        let content = read_text_file(path)?;
         let words = content.lines().filter_map(|line| {
             let line = line.trim();
-            if line.len() < min_word_len {
+            if text::grapheme_len(line) < min_word_len {
                 None
             } else {
                 Some(line)
             }
         });
-        Ok(Self::from_words(words))
+        Self::from_words(words).non_empty()
+    }
+
+    /// all words of a hunspell dictionary with its affix file, e.g. `de_DE.dic` and `de_DE.aff`.
+    pub fn from_hunspell_files(
+        dic_path: impl AsRef<Path>,
+        aff_path: impl AsRef<Path>,
+        min_word_len: usize,
+    ) -> Result<Self, CorpusError> {
+        let (dic_path, aff_path) = (dic_path.as_ref(), aff_path.as_ref());
+        let hunspell_error = |path: &Path| {
+            let path = path.to_path_buf();
+            move |source| CorpusError::Hunspell { path, source }
+        };
+        let aff = read_file(aff_path)?;
+        let dic = read_file(dic_path)?;
+        let dic = hunspell::decode(&dic, &aff).map_err(hunspell_error(dic_path))?;
+        let aff = hunspell::decode(&aff, &aff).map_err(hunspell_error(aff_path))?;
+        let words = hunspell::expand(&dic, &aff).map_err(hunspell_error(aff_path))?;
+        Self::from_words(
+            words
+                .into_iter()
+                .filter(|w| text::grapheme_len(w) >= min_word_len),
+        )
+        .non_empty()
+    }
+
+    /// one word per line, the most frequent one first (like `english10000.txt`, `english3000.txt` is sorted alphabetically).
+    ///
+    /// the ranks are counted before words shorter than `min_word_len` are left out.
+    pub fn from_ranked_txt_file(
+        path: impl AsRef<Path>,
+        min_word_len: usize,
+        weighting: LetterWeighting,
+    ) -> Result<Self, CorpusError> {
+        let content = read_text_file(path)?;
+        let words = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .enumerate()
+            .filter(|(_, line)| text::grapheme_len(line) >= min_word_len)
+            .map(|(i, line)| (line.to_string(), Some(i + 1)));
+        Self::from_optionally_ranked_words(words, weighting).non_empty()
+    }
+
+    /// the corpus if it has words, loading a file that leaves no words is an error.
+    fn non_empty(self) -> Result<Self, CorpusError> {
+        if self.words.is_empty() {
+            Err(CorpusError::Empty)
+        } else {
+            Ok(self)
+        }
+    }
+
+    /// the frequency rank of a word (in upper case, like the corpus words), 1 for the most frequent one.
+    ///
+    /// None if the word is not in the corpus or the corpus was not loaded with ranks.
+    pub fn rank(&self, word: &str) -> Option<usize> {
+        self.ranks.get(word).copied()
     }
 
-    pub fn random_char(&self) -> char {
-        let v: f64 = thread_rng().gen();
-        let mut acc: f64 = 0.0;
-        for (c, p) in self.char_probabilities.iter() {
-            acc += *p;
-            if acc > v {
-                return *c;
+    /// finds the words with at least `min_len` letters in a line of tiles, from left to right.
+    /// the start and end of each word are tile indices, words never start or end within a multi-letter tile.
+    ///
+    /// overlapping words are handled according to the `policy`.
+    pub fn line_search(
+        &self,
+        line: &[Letter],
+        min_len: usize,
+        policy: OverlapPolicy,
+    ) -> Vec<(String, usize, usize)> {
+        // no single letter words:
+        let min_len = min_len.max(2);
+        let spans = match policy {
+            OverlapPolicy::Greedy => self.line_search_greedy(line, min_len),
+            OverlapPolicy::Longest => self.line_search_longest(line, min_len),
+            OverlapPolicy::All => self.line_search_all(line, min_len),
+        };
+        spans
+            .into_iter()
+            .map(|(s, e)| (line[s..e].iter().flat_map(|l| l.chars()).collect(), s, e))
+            .collect()
+    }
+
+    /// the ends of all words with at least `min_len` letters that start at tile `s`, ascending.
+    fn word_ends(&self, line: &[Letter], s: usize, min_len: usize) -> Vec<usize> {
+        // walk forward from s as long as there are words with this prefix:
+        let mut node = Trie::ROOT;
+        let mut len = 0;
+        let mut ends: Vec<usize> = vec![];
+        for (i, letter) in line.iter().enumerate().skip(s) {
+            for c in letter.chars() {
+                let Some(next) = self.trie.step(node, c) else {
+                    return ends;
+                };
+                node = next;
+                len += 1;
+            }
+            if self.trie.is_word(node) && len >= min_len {
+                ends.push(i + 1);
             }
         }
-        panic!("should be unreachable")
+        ends
     }
 
-    pub fn line_search(&self, line: &[char]) -> Vec<(String, usize, usize)> {
-        let len = line.len();
-        let mut s: usize = 0;
+    /// from each start the longest word is taken, words that are fully contained in the previous match are skipped.
+    fn line_search_greedy(&self, line: &[Letter], min_len: usize) -> Vec<(usize, usize)> {
         let mut matches: Vec<_> = vec![];
         let mut minend: usize = 0;
-        while s < len - 2 {
-            let mut e = len;
-            'inner: while e > (s + 1).max(minend) {
-                let candidate: String = line[s..e].iter().collect();
-                if self.words.contains(&candidate) {
-                    // add the word:
-                    matches.push((candidate, s, e));
-                    // set the minenc variable, such that words that are within this word are not included
+        for s in 0..line.len() {
+            if let Some(&e) = self.word_ends(line, s, min_len).last() {
+                // set the minend variable, such that words that are within this word are not included
+                if e > minend {
+                    matches.push((s, e));
                     minend = e;
-                    break 'inner;
                 }
-                e -= 1;
             }
-            s += 1;
         }
         matches
     }
+
+    /// the non overlapping words that cover the most tiles of the line.
+    /// among equally good choices fewer (so longer) words win, then words that start earlier.
+    fn line_search_longest(&self, line: &[Letter], min_len: usize) -> Vec<(usize, usize)> {
+        let len = line.len();
+        // best[i] is the best choice for line[i..]: (tiles covered, number of words, end of the word starting at i)
+        let mut best: Vec<(usize, usize, Option<usize>)> = vec![(0, 0, None); len + 1];
+        for s in (0..len).rev() {
+            let (covered, count, _) = best[s + 1];
+            best[s] = (covered, count, None);
+            for e in self.word_ends(line, s, min_len).into_iter().rev() {
+                let (covered, count) = (best[e].0 + e - s, best[e].1 + 1);
+                let (best_covered, best_count, best_end) = best[s];
+                // more tiles covered, then fewer words, then taking the word over skipping to s + 1:
+                let better =
+                    (covered, best_count, best_end.is_none()) > (best_covered, count, false);
+                if better {
+                    best[s] = (covered, count, Some(e));
+                }
+            }
+        }
+
+        let mut matches: Vec<_> = vec![];
+        let mut s = 0;
+        while s < len {
+            match best[s].2 {
+                Some(e) => {
+                    matches.push((s, e));
+                    s = e;
+                }
+                None => s += 1,
+            }
+        }
+        matches
+    }
+
+    /// every word in the line, also the ones contained in other words.
+    fn line_search_all(&self, line: &[Letter], min_len: usize) -> Vec<(usize, usize)> {
+        (0..line.len())
+            .flat_map(|s| {
+                self.word_ends(line, s, min_len)
+                    .into_iter()
+                    .map(move |e| (s, e))
+            })
+            .collect()
+    }
+}
+
+/// where the words of a game come from. replays and saved games store it instead of the words.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CorpusSource {
+    /// the word list, in the `format`
+    pub path: PathBuf,
+    #[serde(default)]
+    pub format: WordListFormat,
+    /// removes the diacritics from all words, see `Corpus::with_folded_diacritics`
+    #[serde(default)]
+    pub fold_diacritics: bool,
+    /// files with the common and with the uncommon words, all other words are rare.
+    /// without them every word is common.
+    #[serde(default)]
+    pub rarity_tier_paths: Option<(PathBuf, PathBuf)>,
+    /// tiles that hold several letters, see `Corpus::with_multi_letter_tiles`
+    #[serde(default)]
+    pub multi_letter_tiles: Vec<Letter>,
+}
+
+impl CorpusSource {
+    /// the words of a text file with one word per line, without rarity tiers and multi-letter tiles.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            format: WordListFormat::Txt,
+            fold_diacritics: false,
+            rarity_tier_paths: None,
+            multi_letter_tiles: vec![],
+        }
+    }
+
+    /// reads the words of at least `min_word_len` letters and the rarity tiers.
+    pub fn load(&self, min_word_len: usize) -> Result<Corpus, CorpusError> {
+        let mut corpus = match &self.format {
+            WordListFormat::Txt => Corpus::from_txt_file(&self.path, min_word_len)?,
+            WordListFormat::RankedTxt { weighting } => {
+                Corpus::from_ranked_txt_file(&self.path, min_word_len, *weighting)?
+            }
+            WordListFormat::Hunspell { aff_path } => {
+                Corpus::from_hunspell_files(&self.path, aff_path, min_word_len)?
+            }
+        };
+        if let Some((common_path, uncommon_path)) = &self.rarity_tier_paths {
+            corpus = corpus.with_rarity_tier_files(common_path, uncommon_path)?;
+        }
+        if self.fold_diacritics {
+            corpus = corpus.with_folded_diacritics();
+        }
+        Ok(corpus.with_multi_letter_tiles(self.multi_letter_tiles.iter().copied()))
+    }
+}
+
+/// how the word list of a `CorpusSource` is read.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WordListFormat {
+    /// one word per line, see `Corpus::from_txt_file`
+    #[default]
+    Txt,
+    /// one word per line, the most frequent one first, see `Corpus::from_ranked_txt_file`
+    RankedTxt { weighting: LetterWeighting },
+    /// a hunspell `.dic` file with its affix file, see `Corpus::from_hunspell_files`
+    Hunspell { aff_path: PathBuf },
 }
 
 #[cfg(test)]
 mod test {
-    use crate::models::corpus::Corpus;
+    use crate::models::{
+        corpus::{Corpus, CorpusError, CorpusSource, LetterWeighting, Rarity, WordListFormat},
+        letter::Letter,
+        rules::OverlapPolicy,
+    };
+
+    fn tiles(s: &str) -> Vec<Letter> {
+        s.chars().map(Letter::from).collect()
+    }
 
     #[test]
     fn corpus_line_search() {
         let corpus = Corpus::from_words(["Cat", "Tomb", "Atom", "at", "Tom"]);
         // notice: at is not a part here because it is fully covered by atom:
         assert_eq!(
-            corpus.line_search(&"CATOMB".chars().collect::<Vec<_>>()),
+            corpus.line_search(&tiles("CATOMB"), 3, OverlapPolicy::Greedy),
             vec![("CAT", 0, 3,), ("ATOM", 1, 5,), ("TOMB", 2, 6,),]
                 .into_iter()
                 .map(|(ss, s, e)| (ss.to_string(), s as usize, e as usize))
                 .collect::<Vec<_>>()
         )
     }
-}
 
-// for scoring in future:
-// fn main2() {
-//     let corpus = Corpus::from_txt_file("assets/english3000.txt").unwrap();
-//     let mut probs: Vec<_> = corpus
-//         .char_probabilities
-//         .clone()
-//         .into_iter()
-//         .map(|(c, p)| (c, p * 100.0))
-//         .collect();
-//     probs.sort_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
-//     dbg!(probs);
-// }
+    #[test]
+    fn corpus_line_search_policies() {
+        let corpus = Corpus::from_words(["Cat", "Tomb", "Atom", "at", "Tom"]);
+        let line = tiles("CATOMB");
+        let words = |matches: Vec<(String, usize, usize)>| -> Vec<String> {
+            matches.into_iter().map(|(word, _, _)| word).collect()
+        };
+        // CAT + ATOM would overlap, so only one 4 letter word fits:
+        assert_eq!(
+            words(corpus.line_search(&line, 2, OverlapPolicy::Longest)),
+            vec!["ATOM"]
+        );
+        assert_eq!(
+            words(corpus.line_search(&tiles("CATXTOMB"), 2, OverlapPolicy::Longest)),
+            vec!["CAT", "TOMB"]
+        );
+        assert_eq!(
+            words(corpus.line_search(&line, 2, OverlapPolicy::All)),
+            vec!["CAT", "AT", "ATOM", "TOM", "TOMB"]
+        );
+        assert_eq!(
+            words(corpus.line_search(&line, 3, OverlapPolicy::All)),
+            vec!["CAT", "ATOM", "TOM", "TOMB"]
+        );
+    }
+
+    #[test]
+    fn corpus_ranks() {
+        let words = ["the", "of", "The", "zebra", "quiz"];
+        let corpus = Corpus::from_ranked_words(words, LetterWeighting::Uniform);
+        assert_eq!(corpus.rank("THE"), Some(1));
+        assert_eq!(corpus.rank("OF"), Some(2));
+        // the second "The" does not change the rank, later words keep theirs:
+        assert_eq!(corpus.rank("QUIZ"), Some(5));
+        assert_eq!(corpus.rank("CAT"), None);
+        assert_eq!(Corpus::from_words(words).rank("THE"), None);
+
+        // frequent words dominate the letter statistics:
+        let zipf = Corpus::from_ranked_words(words, LetterWeighting::Zipf);
+        let (t, z) = (Letter::from('T'), Letter::from('Z'));
+        assert!(zipf.letter_probabilities[&t] > corpus.letter_probabilities[&t]);
+        assert!(zipf.letter_probabilities[&z] < corpus.letter_probabilities[&z]);
+        let total: f64 = zipf.letter_probabilities.values().sum();
+        assert!((total - 1.0).abs() < 1e-9);
+
+        let english =
+            Corpus::from_ranked_txt_file("assets/english10000.txt", 3, LetterWeighting::Zipf)
+                .unwrap();
+        assert_eq!(english.rank("THE"), Some(1));
+        // "of" is too short, but still counts for the ranks:
+        assert_eq!(english.rank("OF"), None);
+        assert_eq!(english.rank("AND"), Some(3));
+    }
+
+    #[test]
+    fn corpus_source() {
+        let qu = Letter::try_from("QU").unwrap();
+        let source = CorpusSource {
+            rarity_tier_paths: Some((
+                "assets/english3000.txt".into(),
+                "assets/english10000.txt".into(),
+            )),
+            multi_letter_tiles: vec![qu],
+            ..CorpusSource::new("assets/3esl.txt")
+        };
+        let corpus = source.load(3).unwrap();
+        assert_eq!(corpus.rarity("HOUSE"), Rarity::Common);
+        assert_eq!(corpus.split_into_tiles("QUIZ")[0], qu);
+
+        // without rarity tiers every word is common:
+        let plain = CorpusSource::new("assets/3esl.txt").load(3).unwrap();
+        assert_eq!(plain.rarity("ZYGOTE"), Rarity::Common);
+        assert!(matches!(
+            CorpusSource::new("assets/missing.txt").load(3),
+            Err(CorpusError::Io { .. })
+        ));
+    }
+
+    #[test]
+    fn corpus_source_formats() {
+        let words = |corpus: &Corpus| {
+            let mut words: Vec<String> = corpus.words.iter().cloned().collect();
+            words.sort();
+            words
+        };
+        let hunspell = CorpusSource {
+            format: WordListFormat::Hunspell {
+                aff_path: "assets/test/cafe.aff".into(),
+            },
+            ..CorpusSource::new("assets/test/cafe.dic")
+        };
+        let corpus = hunspell.load(5).unwrap();
+        assert_eq!(words(&corpus), vec!["CAFÉS", "HOUSE", "TABLE", "TABLES"]);
+
+        let folded = CorpusSource {
+            fold_diacritics: true,
+            ..hunspell
+        };
+        assert!(folded.load(4).unwrap().words.contains("CAFES"));
+
+        let ranked = CorpusSource {
+            format: WordListFormat::RankedTxt {
+                weighting: LetterWeighting::Zipf,
+            },
+            ..CorpusSource::new("assets/english10000.txt")
+        };
+        assert_eq!(ranked.load(3).unwrap().rank("THE"), Some(1));
+    }
+
+    #[test]
+    fn corpus_hunspell_files() {
+        let corpus =
+            Corpus::from_hunspell_files("assets/test/cafe.dic", "assets/test/cafe.aff", 4).unwrap();
+        // the dictionary is latin-1 encoded, the affix file adds the plurals:
+        assert!(corpus.words.contains("CAFÉ"));
+        assert!(corpus.words.contains("CAFÉS"));
+        assert!(!corpus.words.contains("HOUSES"));
+        assert!(matches!(
+            Corpus::from_hunspell_files("assets/test/cafe.dic", "assets/missing.aff", 4),
+            Err(CorpusError::Io { .. })
+        ));
+    }
+
+    #[test]
+    fn corpus_load_errors() {
+        assert!(matches!(
+            Corpus::from_txt_file("assets/missing.txt", 3),
+            Err(CorpusError::Io { source, .. }) if source.kind() == std::io::ErrorKind::NotFound
+        ));
+        // no word of english3000.txt is that long:
+        assert!(matches!(
+            Corpus::from_txt_file("assets/english3000.txt", 30),
+            Err(CorpusError::Empty)
+        ));
+    }
+
+    #[test]
+    fn corpus_unicode_words() {
+        let corpus = Corpus::from_words(["Straße", "café", "cafe\u{301}", "Öl"]);
+        // both spellings of café are the same word:
+        let words = |corpus: &Corpus| {
+            let mut words: Vec<String> = corpus.words.iter().cloned().collect();
+            words.sort();
+            words
+        };
+        assert_eq!(words(&corpus), vec!["CAFÉ", "STRAẞE", "ÖL"]);
+        assert_eq!(
+            corpus.line_search(&tiles("XCAFÉX"), 3, OverlapPolicy::Greedy),
+            vec![("CAFÉ".to_string(), 1, 5)]
+        );
+
+        let folded = corpus.with_folded_diacritics();
+        assert_eq!(words(&folded), vec!["CAFE", "OL", "STRAẞE"]);
+        assert!(!folded.letter_probabilities.contains_key(&Letter::from('É')));
+    }
+
+    #[test]
+    fn corpus_multi_letter_tiles() {
+        let qu = Letter::try_from("QU").unwrap();
+        let corpus =
+            Corpus::from_words(["Quit", "Queen", "Qatar", "Equal"]).with_multi_letter_tiles([qu]);
+        assert_eq!(
+            corpus.split_into_tiles("EQUAL"),
+            vec![Letter::from('E'), qu, Letter::from('A'), Letter::from('L')]
+        );
+        // QU replaces Q and U, except for the Q of QATAR:
+        assert!(
+            corpus.letter_probabilities[&qu]
+                > 2.0 * corpus.letter_probabilities[&Letter::from('Q')]
+        );
+        assert!(!corpus.letter_probabilities.contains_key(&Letter::from('U')));
+
+        // the QU tile makes up two of the five letters:
+        let mut line = vec![qu];
+        line.extend(tiles("EENX"));
+        assert_eq!(
+            corpus.line_search(&line, 5, OverlapPolicy::Greedy),
+            vec![("QUEEN".to_string(), 0, 4)]
+        );
+    }
+}
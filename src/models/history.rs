@@ -0,0 +1,178 @@
+use serde::{Deserialize, Serialize};
+
+use super::letterfield::{Letterfield, LetterfieldResolve, Move};
+
+/// the letterfield together with the rest of the game state `S` that undo and redo restore, e.g. the score.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot<S> {
+    pub letterfield: Letterfield,
+    pub state: S,
+}
+
+/// a player move and the resolves (cascades) that followed it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryEntry<S> {
+    pub mv: Move,
+    /// the resolves with matches after the move, in order. empty if the move created no word.
+    pub resolves: Vec<LetterfieldResolve>,
+    /// before the move
+    pub before: Snapshot<S>,
+    /// after the move and all of its resolves
+    pub after: Snapshot<S>,
+}
+
+/// the moves of a game for undo and redo.
+///
+/// a move is recorded in steps, because its resolves only happen one after another:
+/// `begin_move`, then `record_resolve` for each resolve and `end_move` once the letterfield is settled.
+#[derive(Debug, Clone)]
+pub struct MoveHistory<S> {
+    done: Vec<HistoryEntry<S>>,
+    /// the most recently undone move last
+    undone: Vec<HistoryEntry<S>>,
+    /// the move that is still resolving
+    recording: Option<(Move, Snapshot<S>, Vec<LetterfieldResolve>)>,
+}
+
+impl<S> Default for MoveHistory<S> {
+    fn default() -> Self {
+        Self {
+            done: vec![],
+            undone: vec![],
+            recording: None,
+        }
+    }
+}
+
+impl<S> MoveHistory<S> {
+    /// starts recording a move, `before` is the state before the move.
+    pub fn begin_move(&mut self, mv: Move, before: Snapshot<S>) {
+        self.recording = Some((mv, before, vec![]));
+    }
+
+    /// adds a resolve to the move that is being recorded, ignored if there is none.
+    pub fn record_resolve(&mut self, resolve: LetterfieldResolve) {
+        if let Some((_, _, resolves)) = &mut self.recording {
+            resolves.push(resolve);
+        }
+    }
+
+    /// finishes recording the move, `after` is the state once all of its resolves are done.
+    ///
+    /// the moves that were undone before can not be redone anymore.
+    pub fn end_move(&mut self, after: Snapshot<S>) {
+        let Some((mv, before, resolves)) = self.recording.take() else {
+            return;
+        };
+        self.undone.clear();
+        self.done.push(HistoryEntry {
+            mv,
+            resolves,
+            before,
+            after,
+        });
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.is_recording() && !self.done.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.is_recording() && !self.undone.is_empty()
+    }
+
+    /// takes back the last move, returns the state to restore: the one before that move.
+    ///
+    /// None if there is no move to undo or a move is still resolving.
+    pub fn undo(&mut self) -> Option<&Snapshot<S>> {
+        if !self.can_undo() {
+            return None;
+        }
+        let entry = self.done.pop()?;
+        self.undone.push(entry);
+        self.undone.last().map(|entry| &entry.before)
+    }
+
+    /// does the last undone move again, returns the state to restore: the one after that move and its resolves.
+    ///
+    /// None if there is no move to redo or a move is still resolving.
+    pub fn redo(&mut self) -> Option<&Snapshot<S>> {
+        if !self.can_redo() {
+            return None;
+        }
+        let entry = self.undone.pop()?;
+        self.done.push(entry);
+        self.done.last().map(|entry| &entry.after)
+    }
+
+    /// the moves that were done and not undone, the first one first.
+    pub fn entries(&self) -> &[HistoryEntry<S>] {
+        &self.done
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use crate::models::{
+        array2d::Int2,
+        corpus::Corpus,
+        history::{MoveHistory, Snapshot},
+        letter_source::IndependentLetters,
+        letterfield::{Letterfield, Move},
+        rules::RuleSet,
+    };
+
+    #[test]
+    fn history_undo_redo() {
+        let corpus = Corpus::from_words(["Cat"]);
+        let rules = RuleSet::default();
+        let mut letters = IndependentLetters::new(&corpus).unwrap();
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let mut letterfield: Letterfield = "CAK\nDET".to_string().try_into().unwrap();
+        let mut history: MoveHistory<u64> = MoveHistory::default();
+        let snapshot = |letterfield: &Letterfield, score: u64| Snapshot {
+            letterfield: letterfield.clone(),
+            state: score,
+        };
+
+        // moving the T up creates CAT:
+        let mv = Move {
+            from: Int2 { x: 2, y: 1 },
+            to: Int2 { x: 2, y: 0 },
+        };
+        let before = snapshot(&letterfield, 0);
+        history.begin_move(mv, before.clone());
+        letterfield.move_letter(mv.from, mv.to).unwrap();
+        let resolve = letterfield.find_word_matches_and_fill_spaces_randomly(
+            &corpus,
+            &rules,
+            &mut letters,
+            &mut rng,
+        );
+        assert!(!resolve.is_empty());
+        history.record_resolve(resolve);
+        assert!(!history.can_undo());
+        let after = snapshot(&letterfield, 10);
+        history.end_move(after.clone());
+        assert_eq!(history.entries()[0].resolves.len(), 1);
+
+        assert_eq!(history.undo(), Some(&before));
+        assert_eq!(history.undo(), None);
+        assert_eq!(history.redo(), Some(&after));
+        assert_eq!(history.redo(), None);
+
+        // a new move after an undo can not be followed by a redo of the undone move:
+        history.undo();
+        history.begin_move(mv, before.clone());
+        history.end_move(before.clone());
+        assert!(!history.can_redo());
+        assert_eq!(history.entries().len(), 1);
+    }
+}
@@ -0,0 +1,422 @@
+//! reading hunspell dictionaries (a `.dic` word list and its `.aff` affix rules) into plain word lists.
+//!
+//! supported are prefixes and suffixes with their conditions, cross products of both,
+//! one level of continuation suffixes (`SFX A 0 s/B .`), the `FLAG` types and the
+//! `NEEDAFFIX`, `ONLYINCOMPOUND` and `FORBIDDENWORD` flags. compounding is not supported.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HunspellError {
+    /// a line of the `.aff` file that could not be read, 1-based
+    InvalidAffixLine(usize),
+    /// the `SET` encoding is not UTF-8 or ISO8859-1
+    UnsupportedEncoding(String),
+    /// the file is not valid in its encoding
+    InvalidEncoding,
+}
+
+impl Display for HunspellError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HunspellError::InvalidAffixLine(line) => write!(f, "invalid affix rule in line {line}"),
+            HunspellError::UnsupportedEncoding(encoding) => {
+                write!(f, "unsupported dictionary encoding {encoding}")
+            }
+            HunspellError::InvalidEncoding => write!(f, "dictionary is not valid in its encoding"),
+        }
+    }
+}
+
+impl std::error::Error for HunspellError {}
+
+/// all words of the dictionary: the roots and their affixed forms, in the order of the `.dic` file.
+pub fn expand(dic: &str, aff: &str) -> Result<Vec<String>, HunspellError> {
+    let affixes = AffixFile::parse(aff)?;
+    let mut words: Vec<String> = vec![];
+    let mut seen: HashSet<String> = HashSet::new();
+    for (root, flags) in dic_entries(dic, affixes.flag_type) {
+        for word in affixes.expand(root, &flags) {
+            if seen.insert(word.clone()) {
+                words.push(word);
+            }
+        }
+    }
+    Ok(words)
+}
+
+/// decodes the bytes of a `.dic` or `.aff` file with the encoding given by `SET` in the `.aff` file.
+pub fn decode(bytes: &[u8], aff: &[u8]) -> Result<String, HunspellError> {
+    // the SET line itself is always ascii:
+    let encoding = aff
+        .split(|b| *b == b'\n')
+        .filter_map(|line| std::str::from_utf8(line).ok())
+        .find_map(|line| line.trim().strip_prefix("SET "))
+        .map(|encoding| encoding.trim().to_uppercase())
+        .unwrap_or_else(|| "UTF-8".to_string());
+    match encoding.as_str() {
+        "UTF-8" => String::from_utf8(bytes.to_vec()).map_err(|_| HunspellError::InvalidEncoding),
+        // every latin-1 byte is the unicode code point with the same number:
+        "ISO8859-1" | "ISO-8859-1" => Ok(bytes.iter().map(|b| *b as char).collect()),
+        _ => Err(HunspellError::UnsupportedEncoding(encoding)),
+    }
+}
+
+/// how flags are written after the `/` of a `.dic` entry or an affix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum FlagType {
+    /// one character per flag
+    #[default]
+    Char,
+    /// two characters per flag
+    Long,
+    /// comma separated numbers
+    Numeric,
+}
+
+impl FlagType {
+    fn split(self, flags: &str) -> Vec<String> {
+        match self {
+            FlagType::Char => flags.chars().map(String::from).collect(),
+            FlagType::Long => {
+                let chars: Vec<char> = flags.chars().collect();
+                chars.chunks(2).map(|c| c.iter().collect()).collect()
+            }
+            FlagType::Numeric => flags
+                .split(',')
+                .map(|f| f.trim().to_string())
+                .filter(|f| !f.is_empty())
+                .collect(),
+        }
+    }
+}
+
+/// the affix rules of a `.aff` file, by flag.
+#[derive(Debug, Clone, Default)]
+struct AffixFile {
+    flag_type: FlagType,
+    prefixes: HashMap<String, AffixClass>,
+    suffixes: HashMap<String, AffixClass>,
+    /// the root alone is not a word
+    need_affix: Option<String>,
+    only_in_compound: Option<String>,
+    /// neither the root nor its affixed forms are words
+    forbidden_word: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct AffixClass {
+    /// prefixes and suffixes of cross product classes can be combined
+    cross_product: bool,
+    rules: Vec<AffixRule>,
+}
+
+#[derive(Debug, Clone)]
+struct AffixRule {
+    strip: String,
+    add: String,
+    /// flags of the affixed word, e.g. for continuation suffixes
+    flags: Vec<String>,
+    /// the letters the root has to start (prefixes) or end (suffixes) with
+    condition: Vec<CharClass>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum CharClass {
+    Any,
+    Char(char),
+    OneOf(Vec<char>),
+    NoneOf(Vec<char>),
+}
+
+impl CharClass {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            CharClass::Any => true,
+            CharClass::Char(d) => c == *d,
+            CharClass::OneOf(set) => set.contains(&c),
+            CharClass::NoneOf(set) => !set.contains(&c),
+        }
+    }
+
+    /// a condition like `[^aeiou]y`, None if a bracket is not closed.
+    fn parse_condition(condition: &str) -> Option<Vec<CharClass>> {
+        let mut classes: Vec<CharClass> = vec![];
+        let mut chars = condition.chars();
+        while let Some(c) = chars.next() {
+            let class = match c {
+                '.' => CharClass::Any,
+                '[' => {
+                    let mut set: Vec<char> = vec![];
+                    let mut negated = false;
+                    loop {
+                        match chars.next()? {
+                            ']' => break,
+                            '^' if set.is_empty() && !negated => negated = true,
+                            c => set.push(c),
+                        }
+                    }
+                    if negated {
+                        CharClass::NoneOf(set)
+                    } else {
+                        CharClass::OneOf(set)
+                    }
+                }
+                c => CharClass::Char(c),
+            };
+            classes.push(class);
+        }
+        Some(classes)
+    }
+}
+
+impl AffixFile {
+    fn parse(aff: &str) -> Result<Self, HunspellError> {
+        let mut affixes = AffixFile::default();
+        for (i, line) in aff.lines().enumerate() {
+            let invalid = || HunspellError::InvalidAffixLine(i + 1);
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            match tokens.as_slice() {
+                ["FLAG", flag_type, ..] => {
+                    affixes.flag_type = match *flag_type {
+                        "long" => FlagType::Long,
+                        "num" => FlagType::Numeric,
+                        // UTF-8 flags are single characters as well:
+                        _ => FlagType::Char,
+                    }
+                }
+                ["NEEDAFFIX", flag, ..] => affixes.need_affix = Some(flag.to_string()),
+                ["ONLYINCOMPOUND", flag, ..] => affixes.only_in_compound = Some(flag.to_string()),
+                ["FORBIDDENWORD", flag, ..] => affixes.forbidden_word = Some(flag.to_string()),
+                [kind @ ("PFX" | "SFX"), flag, rest @ ..] => {
+                    let classes = if *kind == "PFX" {
+                        &mut affixes.prefixes
+                    } else {
+                        &mut affixes.suffixes
+                    };
+                    match classes.get_mut(*flag) {
+                        // the first line of a class is its header: `SFX A Y 3`
+                        None => {
+                            let cross_product = rest.first().ok_or_else(invalid)? == &"Y";
+                            classes.insert(
+                                flag.to_string(),
+                                AffixClass {
+                                    cross_product,
+                                    rules: vec![],
+                                },
+                            );
+                        }
+                        // then its rules: `SFX A y ies [^aeiou]y`
+                        Some(class) => {
+                            let [strip, add, condition @ ..] = rest else {
+                                return Err(invalid());
+                            };
+                            let (add, flags) = match add.split_once('/') {
+                                Some((add, flags)) => (add, affixes.flag_type.split(flags)),
+                                None => (*add, vec![]),
+                            };
+                            let condition = condition.first().copied().unwrap_or(".");
+                            class.rules.push(AffixRule {
+                                strip: if *strip == "0" { "" } else { strip }.to_string(),
+                                add: if add == "0" { "" } else { add }.to_string(),
+                                flags,
+                                condition: CharClass::parse_condition(condition)
+                                    .ok_or_else(invalid)?,
+                            });
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(affixes)
+    }
+
+    fn has(flags: &[String], flag: &Option<String>) -> bool {
+        flag.as_ref().is_some_and(|flag| flags.contains(flag))
+    }
+
+    /// the root (unless it needs an affix) and all its affixed forms.
+    fn expand(&self, root: &str, flags: &[String]) -> Vec<String> {
+        if Self::has(flags, &self.forbidden_word) {
+            return vec![];
+        }
+        let mut words: Vec<String> = vec![];
+        if !Self::has(flags, &self.need_affix) && !Self::has(flags, &self.only_in_compound) {
+            words.push(root.to_string());
+        }
+
+        // suffixed words that can still get a prefix:
+        let mut cross_product_words: Vec<String> = vec![root.to_string()];
+        for (class, rule) in self.rules(&self.suffixes, flags) {
+            let Some(word) = rule.apply_suffix(root) else {
+                continue;
+            };
+            if !Self::has(&rule.flags, &self.need_affix) {
+                words.push(word.clone());
+            }
+            // one level of continuation suffixes:
+            for (_, continuation) in self.rules(&self.suffixes, &rule.flags) {
+                words.extend(continuation.apply_suffix(&word));
+            }
+            if class.cross_product {
+                cross_product_words.push(word);
+            }
+        }
+
+        for (class, rule) in self.rules(&self.prefixes, flags) {
+            if class.cross_product {
+                words.extend(
+                    cross_product_words
+                        .iter()
+                        .filter_map(|w| rule.apply_prefix(w)),
+                );
+            } else {
+                words.extend(rule.apply_prefix(root));
+            }
+        }
+        words
+    }
+
+    fn rules<'a>(
+        &'a self,
+        classes: &'a HashMap<String, AffixClass>,
+        flags: &'a [String],
+    ) -> impl Iterator<Item = (&'a AffixClass, &'a AffixRule)> {
+        flags
+            .iter()
+            .filter_map(|flag| classes.get(flag))
+            .flat_map(|class| class.rules.iter().map(move |rule| (class, rule)))
+    }
+}
+
+impl AffixRule {
+    fn apply_suffix(&self, word: &str) -> Option<String> {
+        let chars: Vec<char> = word.chars().collect();
+        let n = self.condition.len();
+        if n > chars.len() || !word.ends_with(&self.strip) {
+            return None;
+        }
+        let end = &chars[chars.len() - n..];
+        if !self.condition.iter().zip(end).all(|(c, ch)| c.matches(*ch)) {
+            return None;
+        }
+        let stem = &word[..word.len() - self.strip.len()];
+        Some(format!("{stem}{}", self.add))
+    }
+
+    fn apply_prefix(&self, word: &str) -> Option<String> {
+        let chars: Vec<char> = word.chars().collect();
+        if self.condition.len() > chars.len() || !word.starts_with(&self.strip) {
+            return None;
+        }
+        if !self
+            .condition
+            .iter()
+            .zip(&chars)
+            .all(|(c, ch)| c.matches(*ch))
+        {
+            return None;
+        }
+        let stem = &word[self.strip.len()..];
+        Some(format!("{}{stem}", self.add))
+    }
+}
+
+/// the roots and their flags. the first line (the number of entries) and morphological fields are skipped.
+fn dic_entries(dic: &str, flag_type: FlagType) -> impl Iterator<Item = (&str, Vec<String>)> {
+    dic.lines()
+        .enumerate()
+        .filter_map(move |(i, line)| {
+            let entry = line.split_whitespace().next()?;
+            if i == 0 && entry.parse::<usize>().is_ok() {
+                return None;
+            }
+            Some(match entry.split_once('/') {
+                Some((root, flags)) => (root, flag_type.split(flags)),
+                None => (entry, vec![]),
+            })
+        })
+        .filter(|(root, _)| !root.is_empty())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::models::hunspell::{decode, expand};
+
+    const ENGLISH_AFF: &str = "SET UTF-8
+TRY esianrtolcdugmphbyfvkwz
+
+PFX U Y 1
+PFX U   0     un         .
+
+SFX S Y 3
+SFX S   y     ies        [^aeiou]y
+SFX S   0     s          [aeiou]y
+SFX S   0     s          [^y]
+
+SFX D N 2
+SFX D   0     d          e
+SFX D   0     ed         [^e]
+";
+
+    const ENGLISH_DIC: &str = "4
+happy/U
+play/SD
+kind/US
+bake/D	po:verb
+";
+
+    #[test]
+    fn hunspell_affix_expansion() {
+        let words = expand(ENGLISH_DIC, ENGLISH_AFF).unwrap();
+        let expected = [
+            "happy", "unhappy", "play", "plays", "played", "kind", "kinds", "unkind", "unkinds",
+            "bake", "baked",
+        ];
+        assert_eq!(words, expected);
+    }
+
+    #[test]
+    fn hunspell_long_flags_and_need_affix() {
+        let aff = "FLAG long
+NEEDAFFIX zz
+
+SFX Aa Y 2
+SFX Aa 0 en [^e]
+SFX Aa 0 n e
+
+SFX Bb Y 1
+SFX Bb 0 chen/Cc .
+
+SFX Cc Y 1
+SFX Cc 0 s .
+";
+        let dic = "3\nFrau/Aa\nHaus/AazzBb\nBlume/Aa\n";
+        let words = expand(dic, aff).unwrap();
+        // Haus alone is not a word, Hauschen gets the continuation suffix s:
+        let expected = [
+            "Frau",
+            "Frauen",
+            "Hausen",
+            "Hauschen",
+            "Hauschens",
+            "Blume",
+            "Blumen",
+        ];
+        assert_eq!(words, expected);
+    }
+
+    #[test]
+    fn hunspell_latin1_dictionary() {
+        let aff = b"SET ISO8859-1\nSFX A Y 1\nSFX A 0 e .\n";
+        // "Fu\xdf" is Fuß in latin-1:
+        let dic = b"1\nFu\xdf/A\n";
+        let dic = decode(dic, aff).unwrap();
+        let aff = decode(aff, aff).unwrap();
+        assert_eq!(expand(&dic, &aff).unwrap(), ["Fuß", "Fuße"]);
+    }
+}
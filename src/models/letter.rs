@@ -0,0 +1,94 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+/// the letters on one tile: a single letter, or a short sequence like QU that counts as several letters of a word.
+///
+/// serialized as a string, like a `char`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct Letter([char; Letter::MAX_LEN]);
+
+impl Letter {
+    /// the most letters a single tile can hold
+    pub const MAX_LEN: usize = 3;
+    /// fills up the places after the letters of shorter sequences
+    const PADDING: char = '\0';
+
+    pub fn chars(self) -> impl Iterator<Item = char> {
+        self.0.into_iter().take_while(|c| *c != Self::PADDING)
+    }
+
+    /// true for tiles with more than one letter, like QU.
+    pub fn is_sequence(self) -> bool {
+        self.0[1] != Self::PADDING
+    }
+}
+
+impl From<char> for Letter {
+    fn from(c: char) -> Self {
+        let mut chars = [Self::PADDING; Self::MAX_LEN];
+        chars[0] = c;
+        Letter(chars)
+    }
+}
+
+impl TryFrom<&str> for Letter {
+    type Error = InvalidLetter;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        let mut chars = [Self::PADDING; Self::MAX_LEN];
+        let mut len = 0;
+        for c in s.chars() {
+            if len == Self::MAX_LEN || c == Self::PADDING || c.is_whitespace() {
+                return Err(InvalidLetter(s.to_string()));
+            }
+            chars[len] = c;
+            len += 1;
+        }
+        if len == 0 {
+            return Err(InvalidLetter(s.to_string()));
+        }
+        Ok(Letter(chars))
+    }
+}
+
+impl TryFrom<String> for Letter {
+    type Error = InvalidLetter;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Letter::try_from(s.as_str())
+    }
+}
+
+impl From<Letter> for String {
+    fn from(letter: Letter) -> Self {
+        letter.chars().collect()
+    }
+}
+
+impl Display for Letter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for c in self.chars() {
+            write!(f, "{c}")?;
+        }
+        Ok(())
+    }
+}
+
+/// a string that does not fit on a tile: empty, too long or with whitespace in it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidLetter(pub String);
+
+impl Display for InvalidLetter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:?} is not 1 to {} letters for a tile",
+            self.0,
+            Letter::MAX_LEN
+        )
+    }
+}
+
+impl std::error::Error for InvalidLetter {}
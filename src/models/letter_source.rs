@@ -0,0 +1,327 @@
+use std::collections::BTreeMap;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    corpus::{Corpus, CorpusError},
+    letter::Letter,
+};
+
+/// where the letters of new tiles come from, for the initial letterfield and for refills.
+pub trait LetterSource {
+    fn draw(&mut self, context: &LetterContext, rng: &mut impl Rng) -> Letter;
+}
+
+/// the letters around the cell that a new letter is drawn for, None if unknown or outside the letterfield.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LetterContext {
+    pub below: Option<Letter>,
+    pub left: Option<Letter>,
+    pub right: Option<Letter>,
+}
+
+/// every letter is drawn independently with its probability in the corpus.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndependentLetters {
+    /// ordered by letter, so that sampling from it only depends on the rng.
+    letter_probabilities: BTreeMap<Letter, f64>,
+}
+
+/// the letter probabilities of the corpus, an error if there are none to draw from.
+fn letter_probabilities(corpus: &Corpus) -> Result<BTreeMap<Letter, f64>, CorpusError> {
+    if corpus.letter_probabilities.is_empty() {
+        Err(CorpusError::Empty)
+    } else {
+        Ok(corpus.letter_probabilities.clone())
+    }
+}
+
+impl IndependentLetters {
+    pub fn new(corpus: &Corpus) -> Result<Self, CorpusError> {
+        Ok(Self {
+            letter_probabilities: letter_probabilities(corpus)?,
+        })
+    }
+}
+
+impl LetterSource for IndependentLetters {
+    fn draw(&mut self, _context: &LetterContext, rng: &mut impl Rng) -> Letter {
+        let v: f64 = rng.gen();
+        let mut acc: f64 = 0.0;
+        let mut last = None;
+        for (c, p) in self.letter_probabilities.iter() {
+            acc += *p;
+            if acc > v {
+                return *c;
+            }
+            last = Some(*c);
+        }
+        // only reachable through rounding errors, there is at least one letter:
+        last.unwrap_or(Letter::from('?'))
+    }
+}
+
+/// letters are drawn without replacement from a finite pool, like the tiles of a scrabble bag.
+///
+/// this keeps the letters of a board close to the corpus distribution,
+/// no streaks of rare letters and no boards without vowels.
+/// the bag is filled up again once it is empty.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TileBag {
+    /// how many tiles of each letter a full bag holds
+    counts: BTreeMap<Letter, usize>,
+    tiles: Vec<Letter>,
+}
+
+impl TileBag {
+    /// a bag of about `size` tiles, every letter of the corpus is in it at least once.
+    pub fn new(corpus: &Corpus, size: usize) -> Result<Self, CorpusError> {
+        let counts = letter_probabilities(corpus)?
+            .iter()
+            .map(|(c, p)| (*c, ((p * size as f64).round() as usize).max(1)))
+            .collect();
+        Ok(Self {
+            counts,
+            tiles: vec![],
+        })
+    }
+
+    /// number of tiles left until the bag is filled up again.
+    pub fn remaining(&self) -> usize {
+        self.tiles.len()
+    }
+
+    fn fill(&mut self) {
+        for (c, count) in self.counts.iter() {
+            self.tiles.extend(std::iter::repeat_n(*c, *count));
+        }
+    }
+}
+
+impl LetterSource for TileBag {
+    fn draw(&mut self, _context: &LetterContext, rng: &mut impl Rng) -> Letter {
+        if self.tiles.is_empty() {
+            self.fill();
+        }
+        let i = rng.gen_range(0..self.tiles.len());
+        self.tiles.swap_remove(i)
+    }
+}
+
+/// letters are drawn depending on their neighbours, with the tile pair (bigram) statistics of the corpus words.
+///
+/// a letter that often precedes the tile below it or follows the tile left of it in the corpus
+/// is more likely, so refills form new words more often.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BigramLetters {
+    letter_probabilities: BTreeMap<Letter, f64>,
+    /// `followers[a][b]` is how often b directly follows a in the corpus words
+    followers: BTreeMap<Letter, BTreeMap<Letter, usize>>,
+    /// `predecessors[b][a]` is how often a directly precedes b in the corpus words
+    predecessors: BTreeMap<Letter, BTreeMap<Letter, usize>>,
+    /// 0 draws like `IndependentLetters`, 1 follows the bigram statistics,
+    /// higher values make words even more likely and negative values make them less likely.
+    pub wordiness: f64,
+}
+
+impl BigramLetters {
+    pub fn new(corpus: &Corpus, wordiness: f64) -> Result<Self, CorpusError> {
+        let letter_probabilities = letter_probabilities(corpus)?;
+        let mut followers: BTreeMap<Letter, BTreeMap<Letter, usize>> = BTreeMap::new();
+        let mut predecessors: BTreeMap<Letter, BTreeMap<Letter, usize>> = BTreeMap::new();
+        for word in corpus.words.iter() {
+            let tiles = corpus.split_into_tiles(word);
+            for pair in tiles.windows(2) {
+                let (a, b) = (pair[0], pair[1]);
+                *followers.entry(a).or_default().entry(b).or_insert(0) += 1;
+                *predecessors.entry(b).or_default().entry(a).or_insert(0) += 1;
+            }
+        }
+        Ok(Self {
+            letter_probabilities,
+            followers,
+            predecessors,
+            wordiness,
+        })
+    }
+
+    /// probability of `c` next to `neighbour` in the `pairs`,
+    /// smoothed towards the corpus probability of `c` so that it is never 0.
+    fn conditional(
+        &self,
+        pairs: &BTreeMap<Letter, BTreeMap<Letter, usize>>,
+        neighbour: Letter,
+        c: Letter,
+    ) -> f64 {
+        let counts = pairs.get(&neighbour);
+        let count = counts
+            .and_then(|counts| counts.get(&c))
+            .copied()
+            .unwrap_or(0);
+        let total: usize = counts.map(|counts| counts.values().sum()).unwrap_or(0);
+        let p = self.letter_probabilities.get(&c).copied().unwrap_or(0.0);
+        (count as f64 + p) / (total as f64 + 1.0)
+    }
+}
+
+impl LetterSource for BigramLetters {
+    fn draw(&mut self, context: &LetterContext, rng: &mut impl Rng) -> Letter {
+        // the corpus probability of each letter, scaled by how much more likely its neighbours make it:
+        let weights: Vec<(Letter, f64)> = self
+            .letter_probabilities
+            .iter()
+            .map(|(c, p)| {
+                let conditionals = [
+                    // columns are read top to bottom, so the new letter precedes the one below:
+                    context
+                        .below
+                        .map(|below| self.conditional(&self.predecessors, below, *c)),
+                    context
+                        .left
+                        .map(|left| self.conditional(&self.followers, left, *c)),
+                    context
+                        .right
+                        .map(|right| self.conditional(&self.predecessors, right, *c)),
+                ];
+                let weight = conditionals
+                    .into_iter()
+                    .flatten()
+                    .fold(*p, |w, conditional| {
+                        w * (conditional / p).powf(self.wordiness)
+                    });
+                (*c, weight)
+            })
+            .collect();
+
+        let total: f64 = weights.iter().map(|(_, w)| w).sum();
+        let v: f64 = rng.gen::<f64>() * total;
+        let mut acc: f64 = 0.0;
+        for (c, w) in weights.iter() {
+            acc += w;
+            if acc > v {
+                return *c;
+            }
+        }
+        // only reachable through rounding errors, there is at least one letter:
+        weights.last().map(|(c, _)| *c).unwrap_or(Letter::from('?'))
+    }
+}
+
+/// the letter source the game is configured with.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum LetterSourceMode {
+    Independent,
+    /// a tile bag with about this many tiles
+    TileBag(usize),
+    /// bigram conditioned letters with this wordiness
+    Bigram(f64),
+}
+
+/// one of the letter sources, chosen at runtime by a `LetterSourceMode`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum LetterSources {
+    Independent(IndependentLetters),
+    TileBag(TileBag),
+    Bigram(BigramLetters),
+}
+
+impl LetterSources {
+    pub fn new(mode: LetterSourceMode, corpus: &Corpus) -> Result<Self, CorpusError> {
+        Ok(match mode {
+            LetterSourceMode::Independent => {
+                LetterSources::Independent(IndependentLetters::new(corpus)?)
+            }
+            LetterSourceMode::TileBag(size) => LetterSources::TileBag(TileBag::new(corpus, size)?),
+            LetterSourceMode::Bigram(wordiness) => {
+                LetterSources::Bigram(BigramLetters::new(corpus, wordiness)?)
+            }
+        })
+    }
+}
+
+impl LetterSource for LetterSources {
+    fn draw(&mut self, context: &LetterContext, rng: &mut impl Rng) -> Letter {
+        match self {
+            LetterSources::Independent(letters) => letters.draw(context, rng),
+            LetterSources::TileBag(bag) => bag.draw(context, rng),
+            LetterSources::Bigram(letters) => letters.draw(context, rng),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use crate::models::{
+        corpus::Corpus,
+        letter::Letter,
+        letter_source::{BigramLetters, LetterContext, LetterSource, LetterSources, TileBag},
+    };
+
+    #[test]
+    fn tile_bag_draws_every_tile_once_per_fill() {
+        let corpus = Corpus::from_words(["Cat", "Tomb", "Atom", "at", "Tom"]);
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let mut bag = TileBag::new(&corpus, 30).unwrap();
+        bag.draw(&LetterContext::default(), &mut rng);
+        let size = bag.remaining() + 1;
+
+        let mut bag = TileBag::new(&corpus, 30).unwrap();
+        let mut counts: HashMap<Letter, usize> = HashMap::new();
+        for _ in 0..size {
+            *counts
+                .entry(bag.draw(&LetterContext::default(), &mut rng))
+                .or_insert(0) += 1;
+        }
+        assert_eq!(bag.remaining(), 0);
+        // a full bag has the letters in the proportions of the corpus:
+        assert_eq!(counts.values().sum::<usize>(), size);
+        assert!(counts[&Letter::from('T')] > counts[&Letter::from('C')]);
+        assert!(counts.contains_key(&Letter::from('B')));
+
+        // an empty bag is filled up again:
+        bag.draw(&LetterContext::default(), &mut rng);
+        assert_eq!(bag.remaining(), size - 1);
+    }
+
+    #[test]
+    fn bigram_letters_follow_their_neighbours() {
+        let corpus = Corpus::from_words(["Quit", "Quad", "Aqua", "Squid", "Tail", "Data", "Stat"]);
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let after_q = LetterContext {
+            left: Some(Letter::from('Q')),
+            ..Default::default()
+        };
+        let mut count_u = |wordiness: f64| {
+            let mut letters = BigramLetters::new(&corpus, wordiness).unwrap();
+            (0..100)
+                .filter(|_| letters.draw(&after_q, &mut rng) == Letter::from('U'))
+                .count()
+        };
+        // U is always the letter after a Q in the corpus:
+        let independent = count_u(0.0);
+        assert!(independent < 30);
+        assert!(count_u(1.0) > independent);
+        assert!(count_u(3.0) > 90);
+        assert!(count_u(-1.0) < independent);
+    }
+
+    #[test]
+    fn letter_sources_serde_roundtrip() {
+        let corpus = Corpus::from_words(["Cat", "Tomb", "Atom", "at", "Tom"]);
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let mut bag = LetterSources::TileBag(TileBag::new(&corpus, 30).unwrap());
+        bag.draw(&LetterContext::default(), &mut rng);
+        let bigram = LetterSources::Bigram(BigramLetters::new(&corpus, 1.0).unwrap());
+        for letters in [bag, bigram] {
+            let json = serde_json::to_string(&letters).unwrap();
+            let letters2: LetterSources = serde_json::from_str(&json).unwrap();
+            assert_eq!(letters, letters2);
+        }
+    }
+}
@@ -1,67 +1,79 @@
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     fmt::{format, Display},
 };
 
-use bevy::utils::HashMap;
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
 
 use super::{
-    array2d::{Array2D, Array2DIter, Int2},
-    corpus::Corpus,
+    array2d::{Array2D, Array2DError, Array2DIter, Int2},
+    corpus::{Corpus, Rarity},
+    letter::{InvalidLetter, Letter},
+    letter_source::{LetterContext, LetterSource},
+    rules::{OverlapPolicy, RuleSet},
+    text,
 };
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Letterfield {
     id_count: u32,
-    field: Array2D<(u32, char)>,
+    field: Array2D<(u32, Letter)>,
 }
 
 impl Letterfield {
     /// letter can either be moved horizontally or vertically.
     ///
-    /// returns the ids of the tiles that were effected.
-    ///
-    /// # panics
-    ///
-    /// if the porvided positions out of bounds or not in same row/column
-    pub fn move_letter(&mut self, old_pos: Int2, new_pos: Int2) {
-        assert!(old_pos.x == new_pos.x || old_pos.y == new_pos.y);
-
-        assert!(
-            old_pos.x < self.width()
-                && new_pos.x < self.width()
-                && old_pos.y < self.height()
-                && new_pos.y < self.height()
-        );
+    /// returns the tiles that changed their position.
+    /// the letterfield is unchanged if the positions are out of bounds or not in the same row/column.
+    pub fn move_letter(&mut self, old_pos: Int2, new_pos: Int2) -> Result<MoveOutcome, MoveError> {
+        for pos in [old_pos, new_pos] {
+            if pos.x >= self.width() || pos.y >= self.height() {
+                return Err(MoveError::OutOfBounds(pos));
+            }
+        }
+        if old_pos.x != new_pos.x && old_pos.y != new_pos.y {
+            return Err(MoveError::NotCollinear {
+                from: old_pos,
+                to: new_pos,
+            });
+        }
         if old_pos == new_pos {
-            return;
+            return Ok(MoveOutcome::default());
         }
 
+        // the cells from the old to the new position, all their tiles change their position:
+        let cells: Vec<Int2> = if old_pos.y == new_pos.y {
+            let xs = old_pos.x.min(new_pos.x)..=old_pos.x.max(new_pos.x);
+            xs.map(|x| old_pos.with_x(x)).collect()
+        } else {
+            let ys = old_pos.y.min(new_pos.y)..=old_pos.y.max(new_pos.y);
+            ys.map(|y| old_pos.with_y(y)).collect()
+        };
+        let positions_before: HashMap<u32, Int2> =
+            cells.iter().map(|pos| (self.field[*pos].0, *pos)).collect();
+
         let element = self.field[old_pos];
         if new_pos.x < old_pos.x {
             // horizontal move to the left
-            assert_eq!(new_pos.y, old_pos.y);
             let y = new_pos.y;
             for x in (new_pos.x..old_pos.x).rev() {
                 self.field[Int2 { x: x + 1, y }] = self.field[Int2 { x, y }]
             }
         } else if new_pos.x > old_pos.x {
             // horizontal move to the right
-            assert_eq!(new_pos.y, old_pos.y);
             let y = new_pos.y;
             for x in (old_pos.x + 1)..=new_pos.x {
                 self.field[Int2 { x: x - 1, y }] = self.field[Int2 { x, y }]
             }
         } else if new_pos.y < old_pos.y {
             // vertical move up
-            assert_eq!(new_pos.x, old_pos.x);
             let x = new_pos.x;
             for y in (new_pos.y..old_pos.y).rev() {
                 self.field[Int2 { x, y: y + 1 }] = self.field[Int2 { x, y }]
             }
         } else if new_pos.y > old_pos.y {
             // vertical move down
-            assert_eq!(new_pos.x, old_pos.x);
             let x = new_pos.x;
 
             for y in (old_pos.y + 1)..=new_pos.y {
@@ -69,10 +81,27 @@ impl Letterfield {
             }
         }
         self.field[new_pos] = element;
+
+        let mut shifted_tiles: Vec<ShiftedTile> = cells
+            .into_iter()
+            .map(|pos| {
+                let id = self.field[pos].0;
+                ShiftedTile {
+                    id,
+                    from: positions_before[&id],
+                    to: pos,
+                }
+            })
+            .collect();
+        // the moved tile first, then the ones it pushed aside:
+        shifted_tiles.sort_by_key(|tile| tile.to != new_pos);
+        Ok(MoveOutcome { shifted_tiles })
     }
 
-    pub fn new(field: Array2D<(u32, char)>) -> Self {
-        Self { id_count: 0, field }
+    /// ids of new tiles continue after the highest id in the field.
+    pub fn new(field: Array2D<(u32, Letter)>) -> Self {
+        let id_count = field.iter().map(|(_, (id, _))| id + 1).max().unwrap_or(0);
+        Self { id_count, field }
     }
 
     pub fn width(&self) -> usize {
@@ -92,38 +121,162 @@ impl Letterfield {
         self.id_count
     }
 
-    pub fn iter<'a>(&'a self) -> Array2DIter<'a, (u32, char)> {
+    pub fn iter<'a>(&'a self) -> Array2DIter<'a, (u32, Letter)> {
         self.field.iter()
     }
 
-    pub fn random_with_no_matches(width: usize, height: usize, corpus: &Corpus) -> (Self, usize) {
-        let mut letterfield = Letterfield::random(width, height, corpus);
-        let mut c = 0;
-        loop {
-            c += 1;
-            let resolve = letterfield.find_word_matches_and_fill_spaces_randomly(corpus);
+    pub fn random_with_no_matches(
+        width: usize,
+        height: usize,
+        corpus: &Corpus,
+        rules: &RuleSet,
+        constraints: &GenerationConstraints,
+        letters: &mut impl LetterSource,
+        rng: &mut impl Rng,
+    ) -> Result<(Self, usize), GenerationError> {
+        let mut letterfield = Letterfield::random(width, height, letters, rng)?;
+        let c = letterfield
+            .settle(corpus, rules, constraints, letters, rng)?
+            .len()
+            + 1;
+        Ok((letterfield, c))
+    }
+
+    /// a letterfield without matches on which at least `constraints.min_word_moves` moves create a word.
+    ///
+    /// returns the letterfield and the number of boards that were generated.
+    pub fn random_playable(
+        width: usize,
+        height: usize,
+        corpus: &Corpus,
+        rules: &RuleSet,
+        constraints: &GenerationConstraints,
+        letters: &mut impl LetterSource,
+        rng: &mut impl Rng,
+    ) -> Result<(Self, usize), GenerationError> {
+        for attempt in 1..=constraints.max_attempts {
+            // a board whose matches keep refilling into new matches counts as a failed attempt:
+            let letterfield = match Letterfield::random_with_no_matches(
+                width,
+                height,
+                corpus,
+                rules,
+                constraints,
+                letters,
+                rng,
+            ) {
+                Ok((letterfield, _)) => letterfield,
+                Err(GenerationError::EndlessCascade { .. }) => continue,
+                Err(err) => return Err(err),
+            };
+            if letterfield.word_moves(corpus, rules).len() >= constraints.min_word_moves {
+                return Ok((letterfield, attempt));
+            }
+        }
+        Err(GenerationError::AttemptsExhausted {
+            attempts: constraints.max_attempts,
+        })
+    }
+
+    /// shuffles the tiles (keeping their ids) until there are no matches
+    /// and at least `constraints.min_word_moves` moves create a word.
+    ///
+    /// returns the number of shuffles. on error the letterfield is left as it was.
+    pub fn reshuffle(
+        &mut self,
+        corpus: &Corpus,
+        rules: &RuleSet,
+        constraints: &GenerationConstraints,
+        rng: &mut impl Rng,
+    ) -> Result<usize, GenerationError> {
+        let (width, height) = self.dimensions();
+        let mut tiles: Vec<(u32, Letter)> = self.field.cols.iter().flatten().copied().collect();
+        for attempt in 1..=constraints.max_attempts {
+            tiles.shuffle(rng);
+            let cols: Vec<Vec<(u32, Letter)>> = tiles.chunks(height).map(|c| c.to_vec()).collect();
+            let shuffled = Letterfield {
+                id_count: self.id_count,
+                field: Array2D {
+                    width,
+                    height,
+                    cols,
+                },
+            };
+            if shuffled.find_word_matches(corpus, rules).is_empty()
+                && shuffled.word_moves(corpus, rules).len() >= constraints.min_word_moves
+            {
+                *self = shuffled;
+                return Ok(attempt);
+            }
+        }
+        Err(GenerationError::AttemptsExhausted {
+            attempts: constraints.max_attempts,
+        })
+    }
+
+    /// resolves matches and refills until there are no matches left, at most `constraints.max_cascades` times.
+    ///
+    /// returns all the non empty resolves, in order.
+    pub fn settle(
+        &mut self,
+        corpus: &Corpus,
+        rules: &RuleSet,
+        constraints: &GenerationConstraints,
+        letters: &mut impl LetterSource,
+        rng: &mut impl Rng,
+    ) -> Result<Vec<LetterfieldResolve>, GenerationError> {
+        let mut resolves: Vec<LetterfieldResolve> = vec![];
+        while resolves.len() < constraints.max_cascades {
+            let resolve =
+                self.find_word_matches_and_fill_spaces_randomly(corpus, rules, letters, rng);
             if resolve.matches.is_empty() {
-                break (letterfield, c);
+                return Ok(resolves);
             }
+            resolves.push(resolve);
+        }
+        if self.find_word_matches(corpus, rules).is_empty() {
+            Ok(resolves)
+        } else {
+            Err(GenerationError::EndlessCascade {
+                cascades: constraints.max_cascades,
+            })
         }
     }
 
-    pub fn random(width: usize, height: usize, corpus: &Corpus) -> Self {
+    /// a letterfield of random letters, it can contain matches.
+    pub fn random(
+        width: usize,
+        height: usize,
+        letters: &mut impl LetterSource,
+        rng: &mut impl Rng,
+    ) -> Result<Self, GenerationError> {
+        if width == 0 || height == 0 {
+            return Err(GenerationError::EmptyLetterfield { width, height });
+        }
         let mut id_count = 0;
-        let mut cols: Vec<Vec<(u32, char)>> = vec![];
+        let mut cols: Vec<Vec<(u32, Letter)>> = vec![];
         for _ in 0..width {
-            let mut col: Vec<(u32, char)> = vec![];
-            for _ in 0..height {
-                let c = corpus.random_char();
+            // columns are filled from the top, so only the left neighbour is known:
+            let left_letters: Vec<Option<Letter>> = match cols.last() {
+                Some(left_col) => left_col.iter().map(|(_, c)| Some(*c)).collect(),
+                None => vec![None; height],
+            };
+            let mut col: Vec<(u32, Letter)> = vec![];
+            for left in left_letters {
+                let context = LetterContext {
+                    left,
+                    ..Default::default()
+                };
+                let c = letters.draw(&context, rng);
                 col.push((id_count, c));
                 id_count += 1;
             }
             cols.push(col);
         }
 
-        let field = Array2D::try_from(cols).unwrap();
+        let field = Array2D::try_from(cols).expect("all columns have the same height");
 
-        Self { id_count, field }
+        Ok(Self { id_count, field })
     }
 
     // pub fn chars_and_positions(&self) -> Vec<(char, Int2)> {
@@ -142,99 +295,100 @@ impl Letterfield {
     /// Y O U T U B E   
     /// O O O O O O O
     /// then only YOUTUBE and TO are matches, not TUBE YOU or BE, because they are already contained in a match.s
-    pub fn find_word_matches(&self, corpus: &Corpus) -> Vec<WordMatch> {
+    pub fn find_word_matches(&self, corpus: &Corpus, rules: &RuleSet) -> Vec<WordMatch> {
+        self.line_matches(corpus, rules).matches()
+    }
+
+    /// like `find_word_matches`, but keeps the matches grouped by line,
+    /// such that they can be updated after a move without scanning the whole field again.
+    pub fn line_matches(&self, corpus: &Corpus, rules: &RuleSet) -> LineMatches {
         // for horizontal matches: check each line:
         //      start from left with start: 0 with end: width
         //      if hit, can return for that line
         //      if not hit, decrement end, until end == start+2 (no single letter words allowed)
         //          if still no hit, increment start by one. (until start is width - 2)
 
-        let mut word_matches: Vec<WordMatch> = vec![];
-
-        // check all cols:
-        for (line, start) in self.field.cols_2() {
-            let line_index_to_tile = |i| {
-                let pos = start.with_y(i);
-                let (id, ch) = self.field[pos];
-                (id, ch, pos)
-            };
-            // check all letters same:
-            let first = line.first().unwrap();
-            if line.iter().all(|e| e == first) {
-                let tiles = (0..line.len()).map(line_index_to_tile).collect();
-                let word_match = WordMatch {
-                    tiles,
-                    direction: Direction::Column,
-                    kind: WordMatchKind::SameLetterRow(*first),
-                };
-                word_matches.push(word_match);
-                continue;
-            }
-            // check words:
-            for (word, s, e) in corpus.line_search(&line[..]) {
-                let tiles = (s..e).map(line_index_to_tile).collect();
-                let word_match = WordMatch {
-                    tiles,
-                    direction: Direction::Column,
-                    kind: WordMatchKind::Word(word),
-                };
-                word_matches.push(word_match);
+        let mut lines: Vec<(Line, Vec<Letter>)> = vec![];
+        let mut add_lines = |direction: Direction, letter_lines: Vec<(Vec<Letter>, Int2)>| {
+            for (letters, start) in letter_lines {
+                lines.push((Line { direction, start }, letters));
             }
+        };
+        if rules.columns {
+            add_lines(Direction::Column, self.field.cols_2());
         }
-        // check all rows:
-        for (line, start) in self.field.rows_2() {
-            let line_index_to_tile = |i| {
-                let pos = start.with_x(i);
-                let (id, ch) = self.field[pos];
-                (id, ch, pos)
-            };
-            // check all letters same:
-            let first = line.first().unwrap();
-            if line.iter().all(|e| e == first) {
-                let tiles = (0..line.len()).map(line_index_to_tile).collect();
-                let word_match = WordMatch {
-                    tiles,
-                    direction: Direction::Row,
-                    kind: WordMatchKind::SameLetterRow(*first),
-                };
-                word_matches.push(word_match);
-                continue;
-            }
-            // check words:
-            for (word, s, e) in corpus.line_search(&line[..]) {
-                let tiles = (s..e).map(line_index_to_tile).collect();
-                let word_match = WordMatch {
-                    tiles,
-                    direction: Direction::Row,
-                    kind: WordMatchKind::Word(word),
-                };
-                word_matches.push(word_match);
-            }
+        if rules.rows {
+            add_lines(Direction::Row, self.field.rows_2());
         }
-        // check top-left to bottom-right diags:
-        for (line, start) in self.field.diags_2(3) {
-            for (word, s, e) in corpus.line_search(&line[..]) {
-                let tiles = (s..e)
-                    .map(|i| {
-                        let pos = Int2 {
-                            x: start.x + i,
-                            y: start.y + i,
-                        };
-                        let (id, ch) = self.field[pos];
-                        (id, ch, pos)
-                    })
-                    .collect();
+        // top-left to bottom-right diags:
+        if rules.diagonals {
+            let min_len = rules.min_word_length(Direction::Diagonal);
+            add_lines(Direction::Diagonal, self.field.diags_2(min_len));
+        }
+        // bottom-left to top-right diags:
+        if rules.anti_diagonals {
+            let min_len = rules.min_word_length(Direction::AntiDiagonal);
+            add_lines(Direction::AntiDiagonal, self.field.anti_diags_2(min_len));
+        }
+
+        let lines = lines
+            .into_iter()
+            .map(|(line, letters)| (line, self.scan_line(corpus, rules, line, &letters)))
+            .collect();
+        LineMatches { lines }
+    }
+
+    fn line_letters(&self, line: Line) -> Vec<Letter> {
+        line.positions(self.width(), self.height())
+            .map(|pos| self.field[pos].1)
+            .collect()
+    }
+
+    fn scan_line(
+        &self,
+        corpus: &Corpus,
+        rules: &RuleSet,
+        line: Line,
+        letters: &[Letter],
+    ) -> Vec<WordMatch> {
+        let line_index_to_tile = |i| {
+            let pos = line.pos(i);
+            let (id, ch) = self.field[pos];
+            (id, ch, pos)
+        };
+        // check all letters same (only in cols and rows):
+        if rules.same_letter_rows && matches!(line.direction, Direction::Column | Direction::Row) {
+            let first = letters.first().unwrap();
+            if letters.iter().all(|e| e == first) {
+                let tiles = (0..letters.len()).map(line_index_to_tile).collect();
                 let word_match = WordMatch {
                     tiles,
-                    direction: Direction::Row,
-                    kind: WordMatchKind::Word(word),
+                    direction: line.direction,
+                    start: line.pos(0),
+                    end: line.pos(letters.len() - 1),
+                    kind: WordMatchKind::SameLetterRow(*first),
+                    overlap_policy: rules.overlap_policy,
                 };
-                word_matches.push(word_match);
+                return vec![word_match];
             }
         }
-
-        // return all matches found:
-        word_matches
+        // check words:
+        let min_len = rules.min_word_length(line.direction);
+        corpus
+            .line_search(letters, min_len, rules.overlap_policy)
+            .into_iter()
+            .map(|(word, s, e)| {
+                let rarity = corpus.rarity(&word);
+                WordMatch {
+                    tiles: (s..e).map(line_index_to_tile).collect(),
+                    direction: line.direction,
+                    start: line.pos(s),
+                    end: line.pos(e - 1),
+                    kind: WordMatchKind::Word(word, rarity),
+                    overlap_policy: rules.overlap_policy,
+                }
+            })
+            .collect()
     }
 
     pub fn to_detail_string(&self) -> String {
@@ -254,50 +408,302 @@ impl Letterfield {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WordMatch {
     // char, x as in -->, y as in |
     //                            V
-    pub tiles: Vec<(u32, char, Int2)>,
+    pub tiles: Vec<(u32, Letter, Int2)>,
     pub direction: Direction,
+    /// position of the first letter
+    pub start: Int2,
+    /// position of the last letter
+    pub end: Int2,
     pub kind: WordMatchKind,
+    /// the policy that was used to pick this match among overlapping words of its line
+    pub overlap_policy: OverlapPolicy,
 }
 
 // pub enum WordMatchKind
 
-#[derive(Debug, Clone)]
+/// the reading direction of a match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Direction {
+    /// top to bottom
     Column,
+    /// left to right
     Row,
+    /// top-left to bottom-right
     Diagonal,
+    /// bottom-left to top-right
+    AntiDiagonal,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum WordMatchKind {
-    Word(String),
-    SameLetterRow(char),
+    Word(String, Rarity),
+    SameLetterRow(Letter),
+}
+
+/// requirements for generated and reshuffled letterfields.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GenerationConstraints {
+    /// at least this many moves have to create a word
+    pub min_word_moves: usize,
+    /// boards (or shuffles) that are tried before giving up
+    pub max_attempts: usize,
+    /// resolves in a row before the matches are considered to never end
+    pub max_cascades: usize,
+}
+
+impl Default for GenerationConstraints {
+    fn default() -> Self {
+        Self {
+            min_word_moves: 1,
+            max_attempts: 100,
+            max_cascades: 50,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GenerationError {
+    /// none of the generated boards satisfied the constraints
+    AttemptsExhausted { attempts: usize },
+    /// every refill created new matches, e.g. because the corpus is tiny
+    EndlessCascade { cascades: usize },
+    /// the letterfield would have no tiles
+    EmptyLetterfield { width: usize, height: usize },
+}
+
+impl Display for GenerationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GenerationError::AttemptsExhausted { attempts } => write!(
+                f,
+                "no letterfield satisfied the generation constraints after {attempts} attempts"
+            ),
+            GenerationError::EndlessCascade { cascades } => write!(
+                f,
+                "the letterfield still had matches after {cascades} cascades"
+            ),
+            GenerationError::EmptyLetterfield { width, height } => write!(
+                f,
+                "the letterfield needs at least one row and column, not {width}x{height}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GenerationError {}
+
+/// the tiles that changed their position through `Letterfield::move_letter`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MoveOutcome {
+    /// the moved tile first, then the tiles it pushed aside. empty if the tile was moved to where it was.
+    pub shifted_tiles: Vec<ShiftedTile>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShiftedTile {
+    pub id: u32,
+    pub from: Int2,
+    pub to: Int2,
+}
+
+/// why `Letterfield::move_letter` rejected a move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveError {
+    /// the position is outside of the letterfield
+    OutOfBounds(Int2),
+    /// the positions are neither in the same row nor in the same column
+    NotCollinear { from: Int2, to: Int2 },
+}
+
+impl Display for MoveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MoveError::OutOfBounds(pos) => {
+                write!(f, "({}, {}) is outside of the letterfield", pos.x, pos.y)
+            }
+            MoveError::NotCollinear { from, to } => write!(
+                f,
+                "({}, {}) and ({}, {}) are not in the same row or column",
+                from.x, from.y, to.x, to.y
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MoveError {}
+
+/// why a string is not a letterfield, see `Letterfield::try_from`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseLetterfieldError {
+    /// a tile holds no or too many letters
+    InvalidLetter(InvalidLetter),
+    /// the lines are empty or do not have the same number of tiles
+    Shape(Array2DError),
+}
+
+impl Display for ParseLetterfieldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseLetterfieldError::InvalidLetter(err) => write!(f, "invalid tile: {err}"),
+            ParseLetterfieldError::Shape(err) => write!(f, "invalid letterfield shape: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseLetterfieldError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ParseLetterfieldError::InvalidLetter(err) => Some(err),
+            ParseLetterfieldError::Shape(err) => Some(err),
+        }
+    }
+}
+
+/// a tile moved to another cell in its row or column, see `Letterfield::move_letter`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Move {
+    pub from: Int2,
+    pub to: Int2,
+}
+
+/// a column, row or diagonal of the letterfield that is searched for words.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Line {
+    pub direction: Direction,
+    /// position of the first letter of the line
+    pub start: Int2,
+}
+
+impl Line {
+    /// position of the i-th letter of the line
+    pub fn pos(&self, i: usize) -> Int2 {
+        let Int2 { x, y } = self.start;
+        match self.direction {
+            Direction::Column => Int2 { x, y: y + i },
+            Direction::Row => Int2 { x: x + i, y },
+            Direction::Diagonal => Int2 { x: x + i, y: y + i },
+            Direction::AntiDiagonal => Int2 { x: x + i, y: y - i },
+        }
+    }
+
+    /// number of letters in the line, for a letterfield of the given size
+    pub fn len(&self, width: usize, height: usize) -> usize {
+        let Int2 { x, y } = self.start;
+        match self.direction {
+            Direction::Column => height - y,
+            Direction::Row => width - x,
+            Direction::Diagonal => (width - x).min(height - y),
+            Direction::AntiDiagonal => (width - x).min(y + 1),
+        }
+    }
+
+    pub fn positions(self, width: usize, height: usize) -> impl Iterator<Item = Int2> {
+        (0..self.len(width, height)).map(move |i| self.pos(i))
+    }
+
+    pub fn contains(&self, pos: Int2) -> bool {
+        let Int2 { x, y } = self.start;
+        match self.direction {
+            Direction::Column => pos.x == x && pos.y >= y,
+            Direction::Row => pos.y == y && pos.x >= x,
+            Direction::Diagonal => pos.x >= x && pos.y >= y && pos.x - x == pos.y - y,
+            Direction::AntiDiagonal => pos.x >= x && pos.y <= y && pos.x - x == y - pos.y,
+        }
+    }
+}
+
+/// word matches of a letterfield grouped by the line they were found in.
+///
+/// after a move only the lines that were touched by it need to be scanned again,
+/// the matches of all other lines are reused.
+#[derive(Debug, Clone)]
+pub struct LineMatches {
+    lines: Vec<(Line, Vec<WordMatch>)>,
+}
+
+impl LineMatches {
+    /// all matches, in the same order as `Letterfield::find_word_matches` returns them.
+    pub fn matches(&self) -> Vec<WordMatch> {
+        self.lines
+            .iter()
+            .flat_map(|(_, matches)| matches.iter().cloned())
+            .collect()
+    }
+
+    /// rescans all lines that contain a position changed by `Letterfield::move_letter(old_pos, new_pos)`.
+    ///
+    /// `letterfield` is expected to already have the move applied.
+    pub fn update_after_move(
+        &mut self,
+        letterfield: &Letterfield,
+        corpus: &Corpus,
+        rules: &RuleSet,
+        old_pos: Int2,
+        new_pos: Int2,
+    ) {
+        let changed_positions: Vec<Int2> = if old_pos.x == new_pos.x {
+            let x = old_pos.x;
+            (old_pos.y.min(new_pos.y)..=old_pos.y.max(new_pos.y))
+                .map(|y| Int2 { x, y })
+                .collect()
+        } else {
+            let y = old_pos.y;
+            (old_pos.x.min(new_pos.x)..=old_pos.x.max(new_pos.x))
+                .map(|x| Int2 { x, y })
+                .collect()
+        };
+
+        for (line, matches) in self.lines.iter_mut() {
+            if changed_positions.iter().any(|pos| line.contains(*pos)) {
+                let letters = letterfield.line_letters(*line);
+                *matches = letterfield.scan_line(corpus, rules, *line, &letters);
+            }
+        }
+    }
 }
 
 impl TryFrom<String> for Letterfield {
-    type Error = ();
+    type Error = ParseLetterfieldError;
 
     fn try_from(value: String) -> Result<Self, Self::Error> {
-        let mut id = 0;
-        let lines: Vec<Vec<(u32, char)>> = value
+        let lines: Vec<Vec<Letter>> = value
             .lines()
             .map(|line| {
-                line.replace(' ', "")
-                    .to_uppercase()
-                    .trim()
-                    .chars()
+                let line = text::uppercase(line.trim());
+                // tiles are separated by spaces like in `Display`, which allows multi-letter tiles like QU,
+                // without spaces every letter is a tile:
+                if line.contains(' ') {
+                    line.split_whitespace().map(Letter::try_from).collect()
+                } else {
+                    Ok(line.chars().map(Letter::from).collect())
+                }
+            })
+            .collect::<Result<_, _>>()
+            .map_err(ParseLetterfieldError::InvalidLetter)?;
+        let letters = Array2D::try_from(lines)
+            .map_err(ParseLetterfieldError::Shape)?
+            .transpose();
+
+        // ids are given column by column, like in `Letterfield::random`:
+        let mut id = 0;
+        let cols: Vec<Vec<(u32, Letter)>> = letters
+            .cols
+            .into_iter()
+            .map(|col| {
+                col.into_iter()
                     .map(|c| {
                         id += 1;
-                        (id, c)
+                        (id - 1, c)
                     })
-                    .collect::<Vec<(u32, char)>>()
+                    .collect()
             })
             .collect();
-        let field = Array2D::try_from(lines)?.transpose();
+        let field = Array2D::try_from(cols).map_err(ParseLetterfieldError::Shape)?;
 
         Ok(Letterfield::new(field))
     }
@@ -321,13 +727,13 @@ impl Display for Letterfield {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct LetterfieldResolve {
     pub matches: Vec<WordMatch>,
-    pub new_letters: HashMap<u32, (Int2, char)>,
-    pub old_letters: HashMap<u32, (Int2, char)>,
+    pub new_letters: HashMap<u32, (Int2, Letter)>,
+    pub old_letters: HashMap<u32, (Int2, Letter)>,
     /// from, to, ..
-    pub moving_letters: HashMap<u32, (Int2, Int2, char)>,
+    pub moving_letters: HashMap<u32, (Int2, Int2, Letter)>,
 }
 
 impl LetterfieldResolve {
@@ -347,8 +753,11 @@ impl Letterfield {
     pub fn find_word_matches_and_fill_spaces_randomly(
         &mut self,
         corpus: &Corpus,
+        rules: &RuleSet,
+        letters: &mut impl LetterSource,
+        rng: &mut impl Rng,
     ) -> LetterfieldResolve {
-        let matches = self.find_word_matches(corpus);
+        let matches = self.find_word_matches(corpus, rules);
 
         // determine which positions need to be filled:
         let match_positions: HashSet<Int2> = matches
@@ -356,56 +765,63 @@ impl Letterfield {
             .flat_map(|m| m.tiles.iter().map(|(_, _, pos)| *pos))
             .collect();
 
-        let hm_before: HashMap<u32, (Int2, char)> = self
+        let hm_before: HashMap<u32, (Int2, Letter)> = self
             .field
             .iter()
             .map(|(pos, (id, char))| (id, (pos, char)))
             .collect();
 
-        // nerf old letters out and let other letters in that column slide down:
-
-        let cols = std::mem::take(&mut self.field.cols);
-
-        let remove_match_positions_from_column_fill_start_with_random =
-            |col: Vec<(u32, char)>| -> Vec<(u32, char)> {
-                let mut elements_removed: usize = 0;
-                let mut new_col: Vec<_> = col
-                    .into_iter()
+        // nerf old letters out and let other letters in that column slide down,
+        // the columns are bottom to top until they are filled up again:
+        let height = self.height();
+        let mut cols: Vec<Vec<(u32, Letter)>> = std::mem::take(&mut self.field.cols)
+            .into_iter()
+            .map(|col| {
+                col.into_iter()
                     .rev()
                     .filter(|(id, _char)| {
                         let (pos, _) = hm_before[id];
-                        if match_positions.contains(&pos) {
-                            elements_removed += 1;
-                            false
-                        } else {
-                            true
-                        }
+                        !match_positions.contains(&pos)
                     })
-                    .collect();
-                for _ in 0..elements_removed {
-                    let next_id = self.next_id();
-                    let char = corpus.random_char();
-                    new_col.push((next_id, char));
-                }
-                new_col.reverse();
-                new_col
-            };
+                    .collect()
+            })
+            .collect();
+
+        // fill the empty spaces at the top of each column, from the bottom up and column by column,
+        // such that the letter source knows the tiles below and beside each new letter:
+        for x in 0..cols.len() {
+            while cols[x].len() < height {
+                let i = cols[x].len();
+                let char_at = |col: Option<&Vec<(u32, Letter)>>| col?.get(i).map(|(_, c)| *c);
+                let context = LetterContext {
+                    below: cols[x].last().map(|(_, c)| *c),
+                    left: x.checked_sub(1).and_then(|x| char_at(cols.get(x))),
+                    right: char_at(cols.get(x + 1)),
+                };
+                let next_id = self.next_id();
+                let char = letters.draw(&context, rng);
+                cols[x].push((next_id, char));
+            }
+        }
 
         self.field.cols = cols
             .into_iter()
-            .map(remove_match_positions_from_column_fill_start_with_random)
+            .map(|mut col| {
+                col.reverse();
+                col
+            })
             .collect();
 
-        let hm_after: HashMap<u32, (Int2, char)> = self
+        let hm_after: HashMap<u32, (Int2, Letter)> = self
             .field
             .iter()
             .map(|(pos, (id, char))| (id, (pos, char)))
             .collect();
 
         // determinining the actual changes:
-        let mut new_letters: HashMap<u32, (Int2, char)> = HashMap::new();
-        let mut old_letters: HashMap<u32, (Int2, char)> = HashMap::new();
-        let mut moving_letters: HashMap<u32, (Int2, Int2, char)> = HashMap::new();
+        let mut new_letters: HashMap<u32, (Int2, Letter)> = HashMap::new();
+        let mut old_letters: HashMap<u32, (Int2, Letter)> = HashMap::new();
+        let mut moving_letters: HashMap<u32, (Int2, Int2, Letter)> = HashMap::new();
 
         for (id, (pos_before, char_before)) in &hm_before {
             if let Some((pos_after, char_after)) = hm_after.get(id) {
@@ -439,23 +855,366 @@ impl Letterfield {
 
 #[cfg(test)]
 mod test {
-    use crate::models::{corpus::Corpus, letterfield::Letterfield};
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    use crate::models::{
+        array2d::{Array2DError, Int2},
+        corpus::Corpus,
+        letter::Letter,
+        letter_source::{IndependentLetters, LetterContext, LetterSource},
+        letterfield::{
+            Direction, GenerationConstraints, GenerationError, Letterfield, MoveError,
+            ParseLetterfieldError, ShiftedTile,
+        },
+        rules::RuleSet,
+    };
 
     #[test]
     fn letterfield_to_and_from_str() {
         let corpus = Corpus::from_words(["Cat", "Tomb", "Atom", "at", "Tom"]);
-        let letterfield = Letterfield::random(4, 5, &corpus);
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let mut letters = IndependentLetters::new(&corpus).unwrap();
+        let letterfield = Letterfield::random(4, 5, &mut letters, &mut rng).unwrap();
         let letterfield2: Letterfield = letterfield.to_string().try_into().unwrap();
         assert_eq!(letterfield, letterfield2);
     }
 
+    #[test]
+    fn letterfield_random_without_tiles() {
+        let corpus = Corpus::from_words(["Cat"]);
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let mut letters = IndependentLetters::new(&corpus).unwrap();
+        assert_eq!(
+            Letterfield::random(0, 5, &mut letters, &mut rng),
+            Err(GenerationError::EmptyLetterfield {
+                width: 0,
+                height: 5
+            })
+        );
+        assert!(Letterfield::random(4, 0, &mut letters, &mut rng).is_err());
+    }
+
+    /// a seed whose first random 20x20 board contains matches of at least 5 letters
+    const SEED_WITH_MATCHES: u64 = 8;
+
     #[test]
     fn letterfield_matches() {
         let corpus = Corpus::from_txt_file("assets/english3000.txt", 5).unwrap();
-        let letterfield = Letterfield::random(20, 20, &corpus);
-        // probabilistic test but should be fine: (on average 30 matches in 20x20)
-        assert!(!letterfield.find_word_matches(&corpus).is_empty());
-        let (letterfield, _) = Letterfield::random_with_no_matches(20, 20, &corpus);
-        assert!(letterfield.find_word_matches(&corpus).is_empty());
+        let rules = RuleSet::default().with_min_word_length(5);
+        let mut rng = ChaCha8Rng::seed_from_u64(SEED_WITH_MATCHES);
+        let mut letters = IndependentLetters::new(&corpus).unwrap();
+        let letterfield = Letterfield::random(20, 20, &mut letters, &mut rng).unwrap();
+        assert!(!letterfield.find_word_matches(&corpus, &rules).is_empty());
+        let (letterfield, _) = Letterfield::random_with_no_matches(
+            20,
+            20,
+            &corpus,
+            &rules,
+            &GenerationConstraints::default(),
+            &mut letters,
+            &mut rng,
+        )
+        .unwrap();
+        assert!(letterfield.find_word_matches(&corpus, &rules).is_empty());
+    }
+
+    #[test]
+    fn letterfield_incremental_matches() {
+        let corpus = Corpus::from_txt_file("assets/english3000.txt", 3).unwrap();
+        let rules = RuleSet::default();
+        let (width, height) = (12, 9);
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let mut letters = IndependentLetters::new(&corpus).unwrap();
+        let mut letterfield = Letterfield::random(width, height, &mut letters, &mut rng).unwrap();
+        let mut line_matches = letterfield.line_matches(&corpus, &rules);
+        for _ in 0..200 {
+            let old_pos = Int2 {
+                x: rng.gen_range(0..width),
+                y: rng.gen_range(0..height),
+            };
+            let new_pos = if rng.gen() {
+                old_pos.with_x(rng.gen_range(0..width))
+            } else {
+                old_pos.with_y(rng.gen_range(0..height))
+            };
+            letterfield.move_letter(old_pos, new_pos).unwrap();
+            line_matches.update_after_move(&letterfield, &corpus, &rules, old_pos, new_pos);
+            assert_eq!(
+                line_matches.matches(),
+                letterfield.find_word_matches(&corpus, &rules)
+            );
+        }
+    }
+
+    #[test]
+    fn letterfield_diagonal_matches() {
+        let corpus = Corpus::from_words(["Cat"]);
+        let rules = RuleSet::default();
+
+        let letterfield: Letterfield = "CXY\nXAX\nYXT".to_string().try_into().unwrap();
+        let matches = letterfield.find_word_matches(&corpus, &rules);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].direction, Direction::Diagonal);
+        assert_eq!(matches[0].start, Int2 { x: 0, y: 0 });
+        assert_eq!(matches[0].end, Int2 { x: 2, y: 2 });
+
+        let letterfield: Letterfield = "YXT\nXAX\nCXY".to_string().try_into().unwrap();
+        let matches = letterfield.find_word_matches(&corpus, &rules);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].direction, Direction::AntiDiagonal);
+        assert_eq!(matches[0].start, Int2 { x: 0, y: 2 });
+        assert_eq!(matches[0].end, Int2 { x: 2, y: 0 });
+    }
+
+    #[test]
+    fn letterfield_seeded_generation_and_refills() {
+        let corpus = Corpus::from_txt_file("assets/english3000.txt", 3).unwrap();
+        let rules = RuleSet::default();
+        let generate = |seed: u64| {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            let mut letters = IndependentLetters::new(&corpus).unwrap();
+            let mut letterfield = Letterfield::random(10, 8, &mut letters, &mut rng).unwrap();
+            let first = letterfield.clone();
+            letterfield.find_word_matches_and_fill_spaces_randomly(
+                &corpus,
+                &rules,
+                &mut letters,
+                &mut rng,
+            );
+            (first, letterfield)
+        };
+        assert_eq!(generate(7), generate(7));
+        assert_ne!(generate(7).0, generate(8).0);
+    }
+
+    #[test]
+    fn letterfield_serde_roundtrip() {
+        let corpus = Corpus::from_txt_file("assets/english3000.txt", 3).unwrap();
+        let rules = RuleSet::default();
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let mut letters = IndependentLetters::new(&corpus).unwrap();
+        let mut letterfield = Letterfield::random(10, 8, &mut letters, &mut rng).unwrap();
+        // refill once, such that the id count is not just the number of tiles:
+        letterfield.find_word_matches_and_fill_spaces_randomly(
+            &corpus,
+            &rules,
+            &mut letters,
+            &mut rng,
+        );
+        let json = serde_json::to_string(&letterfield).unwrap();
+        let letterfield2: Letterfield = serde_json::from_str(&json).unwrap();
+        assert_eq!(letterfield, letterfield2);
+    }
+
+    #[test]
+    fn letterfield_playable_generation() {
+        let corpus = Corpus::from_txt_file("assets/english3000.txt", 3).unwrap();
+        let rules = RuleSet::default();
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let mut letters = IndependentLetters::new(&corpus).unwrap();
+        let constraints = GenerationConstraints {
+            min_word_moves: 3,
+            max_attempts: 50,
+            ..GenerationConstraints::default()
+        };
+        let (letterfield, _) = Letterfield::random_playable(
+            6,
+            6,
+            &corpus,
+            &rules,
+            &constraints,
+            &mut letters,
+            &mut rng,
+        )
+        .unwrap();
+        assert!(letterfield.find_word_matches(&corpus, &rules).is_empty());
+        assert!(letterfield.word_moves(&corpus, &rules).len() >= 3);
+
+        let impossible = GenerationConstraints {
+            min_word_moves: 1000,
+            max_attempts: 3,
+            ..GenerationConstraints::default()
+        };
+        assert_eq!(
+            Letterfield::random_playable(
+                6,
+                6,
+                &corpus,
+                &rules,
+                &impossible,
+                &mut letters,
+                &mut rng
+            ),
+            Err(GenerationError::AttemptsExhausted { attempts: 3 })
+        );
+    }
+
+    #[test]
+    fn letterfield_dead_board_reshuffle() {
+        let corpus = Corpus::from_words(["Cat"]);
+        let rules = RuleSet::default();
+        let constraints = GenerationConstraints::default();
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+
+        // no T on the board, reshuffling cannot help:
+        let mut hopeless: Letterfield = "CAK\nDEF".to_string().try_into().unwrap();
+        assert!(hopeless.is_dead(&corpus, &rules));
+        let before = hopeless.clone();
+        assert!(hopeless
+            .reshuffle(&corpus, &rules, &constraints, &mut rng)
+            .is_err());
+        assert_eq!(hopeless, before);
+
+        // the T can never get next to CA, but a shuffle of the same tiles can fix that:
+        let mut letterfield: Letterfield = "CAK\nTEF".to_string().try_into().unwrap();
+        assert!(letterfield.is_dead(&corpus, &rules));
+        let tiles = |l: &Letterfield| {
+            let mut tiles: Vec<(u32, Letter)> = l.iter().map(|(_, t)| t).collect();
+            tiles.sort();
+            tiles
+        };
+        let before = tiles(&letterfield);
+        letterfield
+            .reshuffle(&corpus, &rules, &constraints, &mut rng)
+            .unwrap();
+        assert!(!letterfield.is_dead(&corpus, &rules));
+        assert!(letterfield.find_word_matches(&corpus, &rules).is_empty());
+        assert_eq!(tiles(&letterfield), before);
+    }
+
+    /// draws Z and remembers what it was asked for.
+    struct RecordingLetters(Vec<LetterContext>);
+
+    impl LetterSource for RecordingLetters {
+        fn draw(&mut self, context: &LetterContext, _rng: &mut impl Rng) -> Letter {
+            self.0.push(*context);
+            Letter::from('Z')
+        }
+    }
+
+    #[test]
+    fn letterfield_refill_context() {
+        let corpus = Corpus::from_words(["Cat"]);
+        let mut letterfield: Letterfield = "CATQ\nXYWV".to_string().try_into().unwrap();
+        let mut letters = RecordingLetters(vec![]);
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        letterfield.find_word_matches_and_fill_spaces_randomly(
+            &corpus,
+            &RuleSet::default(),
+            &mut letters,
+            &mut rng,
+        );
+        assert_eq!(letterfield.to_string(), "Z Z Z Q\nX Y W V");
+        let context =
+            |below: Option<char>, left: Option<char>, right: Option<char>| LetterContext {
+                below: below.map(Letter::from),
+                left: left.map(Letter::from),
+                right: right.map(Letter::from),
+            };
+        assert_eq!(
+            letters.0,
+            vec![
+                context(Some('X'), None, None),
+                context(Some('Y'), Some('Z'), None),
+                context(Some('W'), Some('Z'), Some('Q')),
+            ]
+        );
+    }
+
+    #[test]
+    fn letterfield_unicode_parsing() {
+        let letterfield: Letterfield = "straße\ncaféöl".to_string().try_into().unwrap();
+        assert_eq!(letterfield.to_string(), "S T R A ẞ E\nC A F É Ö L");
+    }
+
+    #[test]
+    fn letterfield_multi_letter_tiles() {
+        let qu = Letter::try_from("QU").unwrap();
+        let corpus = Corpus::from_words(["Quit", "Queen"]).with_multi_letter_tiles([qu]);
+        let letterfield: Letterfield = "QU I T\nX Y Z".to_string().try_into().unwrap();
+        assert_eq!(letterfield.to_string(), "QU I T\nX Y Z");
+        let matches = letterfield.find_word_matches(&corpus, &RuleSet::default());
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].tiles.len(), 3);
+        assert_eq!(matches[0].tiles[0].1, qu);
+
+        let json = serde_json::to_string(&letterfield).unwrap();
+        let letterfield2: Letterfield = serde_json::from_str(&json).unwrap();
+        assert_eq!(letterfield, letterfield2);
+
+        assert!(Letterfield::try_from("QUITE X\nX Y".to_string()).is_err());
+    }
+
+    #[test]
+    fn letterfield_move_outcome() {
+        let mut letterfield: Letterfield = "CAT\nDOG".to_string().try_into().unwrap();
+        // ids are given column by column: C 0, D 1, A 2, O 3, T 4, G 5
+        let outcome = letterfield
+            .move_letter(Int2 { x: 2, y: 0 }, Int2 { x: 0, y: 0 })
+            .unwrap();
+        assert_eq!(letterfield.to_string(), "T C A\nD O G");
+        let shifted = |id, from: (usize, usize), to: (usize, usize)| ShiftedTile {
+            id,
+            from: from.into(),
+            to: to.into(),
+        };
+        assert_eq!(
+            outcome.shifted_tiles,
+            vec![
+                shifted(4, (2, 0), (0, 0)),
+                shifted(0, (0, 0), (1, 0)),
+                shifted(2, (1, 0), (2, 0)),
+            ]
+        );
+        let outcome = letterfield
+            .move_letter(Int2 { x: 1, y: 0 }, Int2 { x: 1, y: 1 })
+            .unwrap();
+        assert_eq!(
+            outcome.shifted_tiles,
+            vec![shifted(0, (1, 0), (1, 1)), shifted(3, (1, 1), (1, 0))]
+        );
+        assert!(letterfield
+            .move_letter(Int2 { x: 1, y: 1 }, Int2 { x: 1, y: 1 })
+            .unwrap()
+            .shifted_tiles
+            .is_empty());
+    }
+
+    #[test]
+    fn letterfield_invalid_moves() {
+        let mut letterfield: Letterfield = "CAT\nDOG".to_string().try_into().unwrap();
+        let before = letterfield.clone();
+        let (a, b, outside) = (
+            Int2 { x: 0, y: 0 },
+            Int2 { x: 1, y: 1 },
+            Int2 { x: 3, y: 0 },
+        );
+        assert_eq!(
+            letterfield.move_letter(a, outside),
+            Err(MoveError::OutOfBounds(outside))
+        );
+        assert_eq!(
+            letterfield.move_letter(a, b),
+            Err(MoveError::NotCollinear { from: a, to: b })
+        );
+        assert_eq!(letterfield, before);
+
+        assert_eq!(
+            Letterfield::try_from("CAT\nDO".to_string()),
+            Err(ParseLetterfieldError::Shape(Array2DError::RaggedLine {
+                index: 1,
+                len: 2,
+                expected: 3
+            }))
+        );
+    }
+
+    #[test]
+    fn letterfield_smaller_than_the_words() {
+        let corpus = Corpus::from_words(["Cat", "Tomb", "Atom"]);
+        let rules = RuleSet::default().with_min_word_length(5);
+        let letterfield: Letterfield = "CAT\nDOG\nTOM".to_string().try_into().unwrap();
+        assert!(letterfield.find_word_matches(&corpus, &rules).is_empty());
     }
 }
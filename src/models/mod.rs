@@ -1,3 +1,14 @@
 pub mod array2d;
 pub mod corpus;
+pub mod history;
+pub mod hunspell;
+pub mod letter;
+pub mod letter_source;
 pub mod letterfield;
+pub mod replay;
+pub mod rules;
+pub mod score;
+pub mod session;
+pub mod solver;
+pub mod text;
+pub mod trie;
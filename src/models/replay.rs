@@ -0,0 +1,369 @@
+use std::{fmt::Display, path::Path, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    array2d::Int2,
+    corpus::{Corpus, CorpusSource},
+    letterfield::{Letterfield, Move},
+    session::{GameSession, GameSettings, SessionError},
+};
+
+/// everything needed to play a game again: the settings it started with and its moves in order.
+///
+/// the letterfield only depends on the seed and the moves, so the replay does not store any boards.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    pub seed: u64,
+    /// the word list the game was played with. the words themselves are not stored.
+    pub corpus: CorpusSource,
+    #[serde(flatten)]
+    pub settings: GameSettings,
+    pub steps: Vec<ReplayStep>,
+}
+
+/// a move and what the game looked like once it and its cascades were resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplayStep {
+    /// in the notation of `Move::fmt`, e.g. `C3>C7`
+    #[serde(with = "move_notation")]
+    pub mv: Move,
+    /// the total score after the move
+    pub score: u64,
+    /// `fingerprint` of the letterfield after the move
+    pub fingerprint: u64,
+}
+
+impl ReplayStep {
+    pub fn new(mv: Move, score: u64, letterfield: &Letterfield) -> Self {
+        Self {
+            mv,
+            score,
+            fingerprint: fingerprint(letterfield),
+        }
+    }
+}
+
+impl Replay {
+    pub fn read(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, content)
+    }
+}
+
+/// a hash of the ids and letters of the letterfield, to notice when a replay plays out differently.
+///
+/// FNV-1a, such that it is the same on every platform and rust version.
+pub fn fingerprint(letterfield: &Letterfield) -> u64 {
+    const OFFSET: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+    let mut hash = OFFSET;
+    let mut write = |value: u64| {
+        for byte in value.to_le_bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(PRIME);
+        }
+    };
+    let (width, height) = letterfield.dimensions();
+    write(width as u64);
+    write(height as u64);
+    for (_, (id, letter)) in letterfield.iter() {
+        write(id as u64);
+        for c in letter.chars() {
+            write(c as u64);
+        }
+    }
+    hash
+}
+
+/// plays a replay without a window, move by move, through a `GameSession` like the game does.
+#[derive(Debug, Clone)]
+pub struct ReplayPlayer<'a> {
+    replay: &'a Replay,
+    pub session: GameSession,
+    moves_played: usize,
+}
+
+impl<'a> ReplayPlayer<'a> {
+    /// starts the game of the replay.
+    pub fn new(replay: &'a Replay, corpus: Corpus) -> Result<Self, ReplayError> {
+        let session = GameSession::new(corpus, replay.settings.clone(), replay.seed)
+            .map_err(ReplayError::Start)?;
+        Ok(Self {
+            replay,
+            session,
+            moves_played: 0,
+        })
+    }
+
+    /// the number of moves played so far, which is the index of the step that `step` plays next.
+    pub fn moves_played(&self) -> usize {
+        self.moves_played
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.moves_played >= self.replay.steps.len()
+    }
+
+    /// plays the next move of the replay and its cascades and compares the result with the replay.
+    ///
+    /// None once all moves are played.
+    pub fn step(&mut self) -> Option<Result<ReplayStep, ReplayError>> {
+        let index = self.moves_played;
+        let expected = *self.replay.steps.get(index)?;
+        let actual = match self.play_move(expected.mv) {
+            Ok(actual) => actual,
+            Err(err) => return Some(Err(err)),
+        };
+        if actual != expected {
+            return Some(Err(ReplayError::Diverged {
+                step: index,
+                expected,
+                actual,
+            }));
+        }
+        Some(Ok(actual))
+    }
+
+    /// plays any move and its cascades, returns the step to record for it.
+    pub fn play_move(&mut self, mv: Move) -> Result<ReplayStep, ReplayError> {
+        let index = self.moves_played;
+        self.moves_played += 1;
+        self.session
+            .play_move(mv)
+            .map_err(|err| ReplayError::Move { step: index, err })?;
+        Ok(ReplayStep::new(
+            mv,
+            self.session.score.total,
+            &self.session.letterfield,
+        ))
+    }
+
+    /// plays all remaining moves, stops at the first divergence.
+    pub fn play_to_end(&mut self) -> Result<(), ReplayError> {
+        while let Some(result) = self.step() {
+            result?;
+        }
+        Ok(())
+    }
+}
+
+/// why a replay could not be played (to the end).
+#[derive(Debug)]
+pub enum ReplayError {
+    /// the game of the replay could not be started
+    Start(SessionError),
+    /// the move of a step could not be played
+    Move { step: usize, err: SessionError },
+    /// the score or letterfield after a step differs from the recorded one
+    Diverged {
+        step: usize,
+        expected: ReplayStep,
+        actual: ReplayStep,
+    },
+}
+
+impl Display for ReplayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReplayError::Start(err) => write!(f, "could not start the game: {err}"),
+            ReplayError::Move { step, err } => write!(f, "step {step}: {err}"),
+            ReplayError::Diverged {
+                step,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "step {step} ({}) diverged: expected score {} and letterfield {:016x}, got score {} and letterfield {:016x}",
+                expected.mv, expected.score, expected.fingerprint, actual.score, actual.fingerprint
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReplayError::Start(err) => Some(err),
+            ReplayError::Move { err, .. } => Some(err),
+            ReplayError::Diverged { .. } => None,
+        }
+    }
+}
+
+/// `C3>C7`: the tile in column C, row 3 moves to column C, row 7.
+///
+/// columns are letters like in a spreadsheet (A to Z, then AA, AB, ..), rows count from 1 at the top.
+impl Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write_cell(f, self.from)?;
+        write!(f, ">")?;
+        write_cell(f, self.to)
+    }
+}
+
+fn write_cell(f: &mut std::fmt::Formatter<'_>, pos: Int2) -> std::fmt::Result {
+    let mut column = vec![];
+    let mut x = pos.x + 1;
+    while x > 0 {
+        x -= 1;
+        column.push((b'A' + (x % 26) as u8) as char);
+        x /= 26;
+    }
+    let column: String = column.into_iter().rev().collect();
+    write!(f, "{column}{}", pos.y + 1)
+}
+
+impl FromStr for Move {
+    type Err = ParseMoveError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseMoveError(s.to_string());
+        let (from, to) = s.split_once('>').ok_or_else(invalid)?;
+        Ok(Move {
+            from: parse_cell(from.trim()).ok_or_else(invalid)?,
+            to: parse_cell(to.trim()).ok_or_else(invalid)?,
+        })
+    }
+}
+
+fn parse_cell(s: &str) -> Option<Int2> {
+    let digits = s.find(|c: char| c.is_ascii_digit())?;
+    let (column, row) = s.split_at(digits);
+    if column.is_empty() || !column.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let mut x: usize = 0;
+    for c in column.chars() {
+        let digit = (c.to_ascii_uppercase() as u8 - b'A') as usize + 1;
+        x = x.checked_mul(26)?.checked_add(digit)?;
+    }
+    let y: usize = row.parse().ok()?;
+    Some(Int2 {
+        x: x - 1,
+        y: y.checked_sub(1)?,
+    })
+}
+
+/// a string that is not a move like `C3>C7`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseMoveError(pub String);
+
+impl Display for ParseMoveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?} is not a move like \"C3>C7\"", self.0)
+    }
+}
+
+impl std::error::Error for ParseMoveError {}
+
+/// serializes a `Move` in its compact notation.
+mod move_notation {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    use crate::models::letterfield::Move;
+
+    pub fn serialize<S: Serializer>(mv: &Move, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(mv)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Move, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::models::{
+        array2d::Int2,
+        corpus::{Corpus, CorpusSource},
+        letter_source::LetterSourceMode,
+        letterfield::{GenerationConstraints, Move},
+        replay::{Replay, ReplayError, ReplayPlayer},
+        rules::RuleSet,
+        score::ScoreConfig,
+        session::GameSettings,
+    };
+
+    #[test]
+    fn move_notation() {
+        let mv = Move {
+            from: Int2 { x: 2, y: 2 },
+            to: Int2 { x: 2, y: 6 },
+        };
+        assert_eq!(mv.to_string(), "C3>C7");
+        assert_eq!("C3>C7".parse::<Move>(), Ok(mv));
+        assert_eq!("c3 > c7".parse::<Move>(), Ok(mv));
+
+        let wide = Move {
+            from: Int2 { x: 26, y: 0 },
+            to: Int2 { x: 51, y: 0 },
+        };
+        assert_eq!(wide.to_string(), "AA1>AZ1");
+        assert_eq!("AA1>AZ1".parse::<Move>(), Ok(wide));
+
+        for invalid in ["", "C3", "C3>", "3>C7", "C0>C7", "C3>7C", "C-3>C7"] {
+            assert!(invalid.parse::<Move>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn replay_playback() {
+        let corpus = Corpus::from_words(["Cat", "Tomb", "Atom", "Act", "Mob", "Tab"]);
+        let mut replay = Replay {
+            seed: 7,
+            corpus: CorpusSource::new("test"),
+            settings: GameSettings {
+                rules: RuleSet::default(),
+                score_config: ScoreConfig::default(),
+                letter_source: LetterSourceMode::Independent,
+                constraints: GenerationConstraints::default(),
+                width: 5,
+                height: 4,
+            },
+            steps: vec![],
+        };
+
+        // record a game by playing the first word move of each board:
+        let mut recorder = ReplayPlayer::new(&replay, corpus.clone()).unwrap();
+        let mut steps = vec![];
+        while steps.len() < 5 && !recorder.session.game_over {
+            let session = &recorder.session;
+            let Some(mv) = session
+                .letterfield
+                .word_moves(&session.corpus, &session.settings.rules)
+                .first()
+                .copied()
+            else {
+                break;
+            };
+            steps.push(recorder.play_move(mv).unwrap());
+        }
+        replay.steps = steps;
+        assert!(!replay.steps.is_empty());
+        assert!(replay.steps.last().unwrap().score > 0);
+
+        // the replay survives serialization and plays out the same:
+        let json = serde_json::to_string(&replay).unwrap();
+        assert!(json.contains(&format!("\"{}\"", replay.steps[0].mv)));
+        let replay: Replay = serde_json::from_str(&json).unwrap();
+        let mut player = ReplayPlayer::new(&replay, corpus.clone()).unwrap();
+        player.play_to_end().unwrap();
+        assert!(player.is_finished());
+
+        // a changed score is noticed:
+        let mut tampered = replay.clone();
+        tampered.steps[0].score += 1;
+        let mut player = ReplayPlayer::new(&tampered, corpus).unwrap();
+        assert!(matches!(
+            player.play_to_end(),
+            Err(ReplayError::Diverged { step: 0, .. })
+        ));
+    }
+}
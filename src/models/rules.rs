@@ -0,0 +1,113 @@
+use serde::{Deserialize, Serialize};
+
+use super::letterfield::Direction;
+
+/// the rules that decide what counts as a match on the letterfield.
+///
+/// game modes can use different rules without touching the matching code.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuleSet {
+    /// search columns (top to bottom) for words
+    pub columns: bool,
+    /// search rows (left to right) for words
+    pub rows: bool,
+    /// search top-left to bottom-right diagonals for words
+    pub diagonals: bool,
+    /// search bottom-left to top-right diagonals for words
+    pub anti_diagonals: bool,
+    /// a full column or row of the same letter is a match
+    pub same_letter_rows: bool,
+    pub min_word_length: MinWordLength,
+    pub overlap_policy: OverlapPolicy,
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        Self {
+            columns: true,
+            rows: true,
+            diagonals: true,
+            anti_diagonals: true,
+            same_letter_rows: true,
+            min_word_length: MinWordLength::all(3),
+            overlap_policy: OverlapPolicy::Greedy,
+        }
+    }
+}
+
+impl RuleSet {
+    pub fn with_min_word_length(mut self, min_word_length: usize) -> Self {
+        self.min_word_length = MinWordLength::all(min_word_length);
+        self
+    }
+
+    pub fn searches(&self, direction: Direction) -> bool {
+        match direction {
+            Direction::Column => self.columns,
+            Direction::Row => self.rows,
+            Direction::Diagonal => self.diagonals,
+            Direction::AntiDiagonal => self.anti_diagonals,
+        }
+    }
+
+    pub fn min_word_length(&self, direction: Direction) -> usize {
+        match direction {
+            Direction::Column => self.min_word_length.column,
+            Direction::Row => self.min_word_length.row,
+            Direction::Diagonal | Direction::AntiDiagonal => self.min_word_length.diagonal,
+        }
+    }
+
+    /// the shortest word that can be matched in any of the searched directions.
+    ///
+    /// shorter words can be left out when loading a corpus.
+    pub fn shortest_word_length(&self) -> usize {
+        [
+            Direction::Column,
+            Direction::Row,
+            Direction::Diagonal,
+            Direction::AntiDiagonal,
+        ]
+        .into_iter()
+        .filter(|d| self.searches(*d))
+        .map(|d| self.min_word_length(d))
+        .min()
+        .unwrap_or(0)
+    }
+}
+
+/// minimum number of letters of a word match, per orientation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MinWordLength {
+    pub column: usize,
+    pub row: usize,
+    /// used for both diagonal orientations
+    pub diagonal: usize,
+}
+
+impl MinWordLength {
+    pub fn all(min_word_length: usize) -> Self {
+        Self {
+            column: min_word_length,
+            row: min_word_length,
+            diagonal: min_word_length,
+        }
+    }
+}
+
+/// how words that overlap within the same line are handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum OverlapPolicy {
+    /// from each start the longest word is taken, words fully contained in the previous match are skipped.
+    ///
+    /// in CATOMB: CAT, ATOM and TOMB, but not AT or TOM.
+    Greedy,
+    /// the set of non overlapping words that covers the most letters.
+    ///
+    /// in CATOMB: ATOM.
+    Longest,
+    /// every word that is found, overlapping or not.
+    ///
+    /// in CATOMB: CAT, AT, ATOM, TOM and TOMB.
+    All,
+}
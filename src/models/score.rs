@@ -0,0 +1,266 @@
+use serde::{Deserialize, Serialize};
+
+use super::{
+    corpus::{Corpus, Rarity},
+    letter::Letter,
+    letterfield::{LetterfieldResolve, WordMatch, WordMatchKind},
+};
+
+/// points for letters that do not appear in the corpus at all.
+pub const UNKNOWN_LETTER_POINTS: u64 = 10;
+
+/// points for a single tile, the rarer the tile in the corpus the more points.
+///
+/// E is worth 1, Q or Z are worth about 7.
+pub fn letter_points(letter: Letter, corpus: &Corpus) -> u64 {
+    match corpus.letter_probabilities.get(&letter) {
+        Some(p) if *p > 0.0 => ((-p.log2()).round() as u64).saturating_sub(2).max(1),
+        _ => UNKNOWN_LETTER_POINTS,
+    }
+}
+
+/// how points are scaled.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScoreConfig {
+    /// multiplier for each cascade depth of a move: the first entry is for the matches the move created itself,
+    /// the second for the matches formed by the refills after that, and so on.
+    /// deeper cascades use the last entry.
+    pub cascade_multipliers: Vec<u64>,
+    /// multiplier for words of each rarity, same letter rows count as common
+    pub rarity_multipliers: RarityMultipliers,
+}
+
+impl Default for ScoreConfig {
+    fn default() -> Self {
+        Self {
+            cascade_multipliers: vec![1, 2, 3, 4, 5],
+            rarity_multipliers: RarityMultipliers::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RarityMultipliers {
+    pub common: u64,
+    pub uncommon: u64,
+    pub rare: u64,
+}
+
+impl Default for RarityMultipliers {
+    fn default() -> Self {
+        Self {
+            common: 1,
+            uncommon: 2,
+            rare: 3,
+        }
+    }
+}
+
+impl RarityMultipliers {
+    pub fn get(&self, rarity: Rarity) -> u64 {
+        match rarity {
+            Rarity::Common => self.common,
+            Rarity::Uncommon => self.uncommon,
+            Rarity::Rare => self.rare,
+        }
+    }
+}
+
+impl ScoreConfig {
+    /// `cascade_depth` starts at 1 for the matches created by the move itself.
+    pub fn cascade_multiplier(&self, cascade_depth: u32) -> u64 {
+        let index = (cascade_depth.max(1) - 1) as usize;
+        self.cascade_multipliers
+            .get(index)
+            .or(self.cascade_multipliers.last())
+            .copied()
+            .unwrap_or(1)
+    }
+}
+
+/// the itemized points of a single match.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatchScore {
+    pub word_match: WordMatch,
+    /// points of each letter of the match, in order
+    pub letter_points: Vec<u64>,
+    /// longer matches multiply the sum of their letter points by more
+    pub length_multiplier: u64,
+    /// 1 for matches created by the move itself, 2 for matches formed by the refills after that, ...
+    pub cascade_depth: u32,
+    pub cascade_multiplier: u64,
+    pub rarity_multiplier: u64,
+    pub points: u64,
+}
+
+impl MatchScore {
+    pub fn new(
+        word_match: &WordMatch,
+        corpus: &Corpus,
+        config: &ScoreConfig,
+        cascade_depth: u32,
+    ) -> Self {
+        let letter_points: Vec<u64> = word_match
+            .tiles
+            .iter()
+            .map(|(_, c, _)| letter_points(*c, corpus))
+            .collect();
+        // a multi-letter tile like QU counts with all of its letters:
+        let letter_count: usize = word_match
+            .tiles
+            .iter()
+            .map(|(_, letter, _)| letter.chars().count())
+            .sum();
+        let length_multiplier = (letter_count as u64).saturating_sub(2).max(1);
+        let cascade_multiplier = config.cascade_multiplier(cascade_depth);
+        let rarity_multiplier = match &word_match.kind {
+            WordMatchKind::Word(_, rarity) => config.rarity_multipliers.get(*rarity),
+            WordMatchKind::SameLetterRow(_) => config.rarity_multipliers.common,
+        };
+        let points = letter_points.iter().sum::<u64>()
+            * length_multiplier
+            * cascade_multiplier
+            * rarity_multiplier;
+        Self {
+            word_match: word_match.clone(),
+            letter_points,
+            length_multiplier,
+            cascade_depth,
+            cascade_multiplier,
+            rarity_multiplier,
+            points,
+        }
+    }
+}
+
+/// the points of all matches of a resolve.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScoreBreakdown {
+    pub matches: Vec<MatchScore>,
+    pub total: u64,
+}
+
+impl ScoreBreakdown {
+    pub fn new(
+        resolve: &LetterfieldResolve,
+        corpus: &Corpus,
+        config: &ScoreConfig,
+        cascade_depth: u32,
+    ) -> Self {
+        let matches: Vec<MatchScore> = resolve
+            .matches
+            .iter()
+            .map(|m| MatchScore::new(m, corpus, config, cascade_depth))
+            .collect();
+        let total = matches.iter().map(|m| m.points).sum();
+        Self { matches, total }
+    }
+}
+
+/// the points of a game so far.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Score {
+    pub total: u64,
+    /// the breakdown of the most recent resolve
+    pub last: ScoreBreakdown,
+    /// number of resolves with matches since the last move, 0 while no move is resolving
+    pub cascade_depth: u32,
+}
+
+impl Score {
+    pub fn add(&mut self, breakdown: ScoreBreakdown) {
+        self.total += breakdown.total;
+        self.last = breakdown;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use crate::models::{
+        corpus::{Corpus, Rarity},
+        letter::Letter,
+        letter_source::IndependentLetters,
+        letterfield::{Letterfield, WordMatchKind},
+        rules::RuleSet,
+        score::{letter_points, ScoreBreakdown, ScoreConfig},
+    };
+
+    #[test]
+    fn score_breakdown() {
+        let corpus = Corpus::from_txt_file("assets/english3000.txt", 3).unwrap();
+        assert!(
+            letter_points(Letter::from('Z'), &corpus) > letter_points(Letter::from('E'), &corpus)
+        );
+        assert_eq!(letter_points(Letter::from('E'), &corpus), 1);
+
+        let mut letterfield: Letterfield = "CATX\nXXXZ\nQZXQ".to_string().try_into().unwrap();
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let resolve = letterfield.find_word_matches_and_fill_spaces_randomly(
+            &corpus,
+            &RuleSet::default(),
+            &mut IndependentLetters::new(&corpus).unwrap(),
+            &mut rng,
+        );
+        let config = ScoreConfig::default();
+        let breakdown = ScoreBreakdown::new(&resolve, &corpus, &config, 1);
+        assert_eq!(breakdown.matches.len(), 1);
+        let cat = &breakdown.matches[0];
+        assert_eq!(cat.letter_points.len(), 3);
+        assert_eq!(cat.length_multiplier, 1);
+        assert_eq!(cat.cascade_multiplier, 1);
+        assert_eq!(cat.points, cat.letter_points.iter().sum::<u64>());
+        assert_eq!(breakdown.total, cat.points);
+
+        // the same match deeper in a cascade:
+        let chained = ScoreBreakdown::new(&resolve, &corpus, &config, 3);
+        assert_eq!(chained.matches[0].cascade_depth, 3);
+        assert_eq!(chained.total, cat.points * 3);
+    }
+
+    #[test]
+    fn score_cascade_multipliers() {
+        let config = ScoreConfig {
+            cascade_multipliers: vec![1, 2, 4],
+            ..Default::default()
+        };
+        assert_eq!(config.cascade_multiplier(1), 1);
+        assert_eq!(config.cascade_multiplier(3), 4);
+        // deeper cascades keep the last multiplier:
+        assert_eq!(config.cascade_multiplier(10), 4);
+    }
+
+    #[test]
+    fn score_rarity_multipliers() {
+        let corpus =
+            Corpus::from_words(["Cat", "Dog", "Emu"]).with_rarity_tiers(["cat"], ["cat", "dog"]);
+        assert_eq!(corpus.rarity("CAT"), Rarity::Common);
+        assert_eq!(corpus.rarity("DOG"), Rarity::Uncommon);
+        assert_eq!(corpus.rarity("EMU"), Rarity::Rare);
+
+        let mut letterfield: Letterfield = "CAT\nDOG\nEMU".to_string().try_into().unwrap();
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let resolve = letterfield.find_word_matches_and_fill_spaces_randomly(
+            &corpus,
+            &RuleSet::default(),
+            &mut IndependentLetters::new(&corpus).unwrap(),
+            &mut rng,
+        );
+        let breakdown = ScoreBreakdown::new(&resolve, &corpus, &ScoreConfig::default(), 1);
+        assert_eq!(breakdown.matches.len(), 3);
+        for m in breakdown.matches.iter() {
+            let WordMatchKind::Word(_, rarity) = &m.word_match.kind else {
+                panic!("only words on this board");
+            };
+            let expected = match rarity {
+                Rarity::Common => 1,
+                Rarity::Uncommon => 2,
+                Rarity::Rare => 3,
+            };
+            assert_eq!(m.rarity_multiplier, expected);
+            assert_eq!(m.points, m.letter_points.iter().sum::<u64>() * expected);
+        }
+    }
+}
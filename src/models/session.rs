@@ -0,0 +1,467 @@
+use std::fmt::Display;
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    array2d::Int2,
+    corpus::{Corpus, CorpusError},
+    history::Snapshot,
+    letter_source::{LetterSourceMode, LetterSources},
+    letterfield::{
+        GenerationConstraints, GenerationError, Letterfield, LetterfieldResolve, Move, MoveError,
+        MoveOutcome,
+    },
+    rules::RuleSet,
+    score::{Score, ScoreBreakdown, ScoreConfig},
+    solver::RankedMove,
+};
+
+/// how a game is played, fixed for the whole game.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GameSettings {
+    pub rules: RuleSet,
+    pub score_config: ScoreConfig,
+    pub letter_source: LetterSourceMode,
+    pub constraints: GenerationConstraints,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl GameSettings {
+    /// checks the settings before a game is started with them.
+    pub fn validate(&self) -> Result<(), SessionError> {
+        if self.width == 0 || self.height == 0 {
+            return Err(SessionError::Generation(
+                GenerationError::EmptyLetterfield {
+                    width: self.width,
+                    height: self.height,
+                },
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// a game without any rendering: the letterfield, the corpus it is played with, the randomness and the score.
+///
+/// a move is applied with `apply_move` and then resolved step by step with `resolve_step`,
+/// the app animates each step while bots, tests and tools can `play_move` at once.
+#[derive(Debug, Clone)]
+pub struct GameSession {
+    pub corpus: Corpus,
+    pub settings: GameSettings,
+    pub seed: u64,
+    pub letterfield: Letterfield,
+    pub letters: LetterSources,
+    /// for the letterfield only: the initial board, all refills and reshuffles
+    pub rng: ChaCha8Rng,
+    pub score: Score,
+    /// the moves played so far
+    pub moves: u32,
+    /// no move creates a word anymore and reshuffling did not help
+    pub game_over: bool,
+}
+
+/// everything besides the letterfield that changes during a game, for undo and saved games.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionState {
+    pub letters: LetterSources,
+    pub rng: ChaCha8Rng,
+    pub score: Score,
+    pub moves: u32,
+    pub game_over: bool,
+}
+
+/// one step of resolving the letterfield after a move, see `GameSession::resolve_step`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResolveStep {
+    /// the matches were removed, the tiles above them fell down and new tiles filled the columns
+    Cascade {
+        resolve: LetterfieldResolve,
+        score: ScoreBreakdown,
+    },
+    /// no move created a word anymore, so the tiles were shuffled
+    Reshuffled { tries: usize },
+    /// no matches are left and there are moves that create words, the move is done
+    Settled,
+    /// no move creates a word anymore and reshuffling did not help, or the cascade never ended
+    GameOver(GenerationError),
+}
+
+impl ResolveStep {
+    /// true for the last step of a move.
+    pub fn is_final(&self) -> bool {
+        matches!(self, ResolveStep::Settled | ResolveStep::GameOver(_))
+    }
+}
+
+/// a move and every step that resolved it, in order. the last step is `Settled` or `GameOver`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoveTimeline {
+    pub outcome: MoveOutcome,
+    pub steps: Vec<ResolveStep>,
+}
+
+impl GameSession {
+    /// a new game, the seed determines the initial letterfield and every refill.
+    pub fn new(corpus: Corpus, settings: GameSettings, seed: u64) -> Result<Self, SessionError> {
+        settings.validate()?;
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let mut letters =
+            LetterSources::new(settings.letter_source, &corpus).map_err(SessionError::Corpus)?;
+        let (letterfield, _) = Letterfield::random_playable(
+            settings.width,
+            settings.height,
+            &corpus,
+            &settings.rules,
+            &settings.constraints,
+            &mut letters,
+            &mut rng,
+        )
+        .map_err(SessionError::Generation)?;
+        Ok(Self {
+            corpus,
+            settings,
+            seed,
+            letterfield,
+            letters,
+            rng,
+            score: Score::default(),
+            moves: 0,
+            game_over: false,
+        })
+    }
+
+    /// continues a game from a snapshot, e.g. of a saved game.
+    pub fn resume(
+        corpus: Corpus,
+        settings: GameSettings,
+        seed: u64,
+        snapshot: Snapshot<SessionState>,
+    ) -> Self {
+        let Snapshot { letterfield, state } = snapshot;
+        Self {
+            corpus,
+            settings,
+            seed,
+            letterfield,
+            letters: state.letters,
+            rng: state.rng,
+            score: state.score,
+            moves: state.moves,
+            game_over: state.game_over,
+        }
+    }
+
+    pub fn snapshot(&self) -> Snapshot<SessionState> {
+        Snapshot {
+            letterfield: self.letterfield.clone(),
+            state: SessionState {
+                letters: self.letters.clone(),
+                rng: self.rng.clone(),
+                score: self.score.clone(),
+                moves: self.moves,
+                game_over: self.game_over,
+            },
+        }
+    }
+
+    /// the snapshot without a drag in progress that moved the tile at `from` to `to`.
+    ///
+    /// a drag already moves the tile in the letterfield, but it is no move until it is dropped.
+    pub fn snapshot_before_drag(
+        &self,
+        from: Int2,
+        to: Int2,
+    ) -> Result<Snapshot<SessionState>, SessionError> {
+        let mut snapshot = self.snapshot();
+        snapshot
+            .letterfield
+            .move_letter(to, from)
+            .map_err(SessionError::Move)?;
+        Ok(snapshot)
+    }
+
+    /// goes back (or forth) to a snapshot of this game, e.g. for undo.
+    pub fn restore(&mut self, snapshot: Snapshot<SessionState>) {
+        let Snapshot { letterfield, state } = snapshot;
+        self.letterfield = letterfield;
+        self.letters = state.letters;
+        self.rng = state.rng;
+        self.score = state.score;
+        self.moves = state.moves;
+        self.game_over = state.game_over;
+    }
+
+    /// moves a tile, `resolve_step` or `resolve` resolve the letterfield after it.
+    pub fn apply_move(&mut self, mv: Move) -> Result<MoveOutcome, SessionError> {
+        if self.game_over {
+            return Err(SessionError::GameOver);
+        }
+        let outcome = self
+            .letterfield
+            .move_letter(mv.from, mv.to)
+            .map_err(SessionError::Move)?;
+        self.moves += 1;
+        Ok(outcome)
+    }
+
+    /// resolves the matches of the letterfield once, or reshuffles it if no move creates a word anymore.
+    ///
+    /// call it until the step `is_final`.
+    pub fn resolve_step(&mut self) -> ResolveStep {
+        let max_cascades = self.settings.constraints.max_cascades;
+        if self.score.cascade_depth as usize >= max_cascades
+            && !self
+                .letterfield
+                .find_word_matches(&self.corpus, &self.settings.rules)
+                .is_empty()
+        {
+            // every refill created new matches, the game would never settle:
+            self.score.cascade_depth = 0;
+            self.game_over = true;
+            return ResolveStep::GameOver(GenerationError::EndlessCascade {
+                cascades: max_cascades,
+            });
+        }
+        let resolve = self.letterfield.find_word_matches_and_fill_spaces_randomly(
+            &self.corpus,
+            &self.settings.rules,
+            &mut self.letters,
+            &mut self.rng,
+        );
+        if !resolve.is_empty() {
+            self.score.cascade_depth += 1;
+            let score = ScoreBreakdown::new(
+                &resolve,
+                &self.corpus,
+                &self.settings.score_config,
+                self.score.cascade_depth,
+            );
+            self.score.add(score.clone());
+            return ResolveStep::Cascade { resolve, score };
+        }
+        self.score.cascade_depth = 0;
+        if !self.letterfield.is_dead(&self.corpus, &self.settings.rules) {
+            return ResolveStep::Settled;
+        }
+        match self.letterfield.reshuffle(
+            &self.corpus,
+            &self.settings.rules,
+            &self.settings.constraints,
+            &mut self.rng,
+        ) {
+            Ok(tries) => ResolveStep::Reshuffled { tries },
+            Err(err) => {
+                self.game_over = true;
+                ResolveStep::GameOver(err)
+            }
+        }
+    }
+
+    /// resolves until the letterfield is settled, returns all steps in order.
+    pub fn resolve(&mut self) -> Vec<ResolveStep> {
+        let mut steps = vec![];
+        loop {
+            let step = self.resolve_step();
+            let is_final = step.is_final();
+            steps.push(step);
+            if is_final {
+                break steps;
+            }
+        }
+    }
+
+    /// `apply_move` and `resolve` at once.
+    pub fn play_move(&mut self, mv: Move) -> Result<MoveTimeline, SessionError> {
+        let outcome = self.apply_move(mv)?;
+        let steps = self.resolve();
+        Ok(MoveTimeline { outcome, steps })
+    }
+
+    /// the best move according to the solver, including the cascades this game would draw.
+    pub fn hint(&self) -> Option<RankedMove> {
+        self.letterfield.hint(
+            &self.corpus,
+            &self.settings.rules,
+            &self.settings.score_config,
+            &self.settings.constraints,
+            &self.letters,
+            &self.rng,
+        )
+    }
+}
+
+/// why a game could not be started or a move not be played.
+#[derive(Debug)]
+pub enum SessionError {
+    /// the letter source could not be created from the corpus
+    Corpus(CorpusError),
+    /// the initial letterfield could not be generated
+    Generation(GenerationError),
+    /// the tile can not be moved there
+    Move(MoveError),
+    /// the game is over, no more moves can be played
+    GameOver,
+}
+
+impl Display for SessionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SessionError::Corpus(err) => write!(f, "could not create the letter source: {err}"),
+            SessionError::Generation(err) => write!(f, "could not create the letterfield: {err}"),
+            SessionError::Move(err) => write!(f, "invalid move: {err}"),
+            SessionError::GameOver => write!(f, "the game is over"),
+        }
+    }
+}
+
+impl std::error::Error for SessionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SessionError::Corpus(err) => Some(err),
+            SessionError::Generation(err) => Some(err),
+            SessionError::Move(err) => Some(err),
+            SessionError::GameOver => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::models::{
+        array2d::Int2,
+        corpus::Corpus,
+        letter_source::LetterSourceMode,
+        letterfield::{GenerationConstraints, GenerationError, Move},
+        rules::RuleSet,
+        score::ScoreConfig,
+        session::{GameSession, GameSettings, ResolveStep, SessionError},
+    };
+
+    fn settings() -> GameSettings {
+        GameSettings {
+            rules: RuleSet::default(),
+            score_config: ScoreConfig::default(),
+            letter_source: LetterSourceMode::Independent,
+            constraints: GenerationConstraints::default(),
+            width: 5,
+            height: 4,
+        }
+    }
+
+    #[test]
+    fn session_timeline() {
+        let corpus = Corpus::from_words(["Cat", "Tomb", "Atom", "Act", "Mob", "Tab"]);
+        let mut session = GameSession::new(corpus.clone(), settings(), 3).unwrap();
+        let before = session.snapshot();
+        let mv = session
+            .letterfield
+            .word_moves(&session.corpus, &session.settings.rules)[0];
+
+        let timeline = session.play_move(mv).unwrap();
+        assert_eq!(session.moves, 1);
+        assert_eq!(timeline.outcome.shifted_tiles[0].from, mv.from);
+        let ResolveStep::Cascade { score, .. } = &timeline.steps[0] else {
+            panic!("the move creates a word");
+        };
+        assert!(score.total > 0);
+        assert!(timeline.steps.last().unwrap().is_final());
+        assert_eq!(
+            timeline.steps.iter().filter(|s| s.is_final()).count(),
+            1,
+            "only the last step is final"
+        );
+        let cascade_points: u64 = timeline
+            .steps
+            .iter()
+            .filter_map(|step| match step {
+                ResolveStep::Cascade { score, .. } => Some(score.total),
+                _ => None,
+            })
+            .sum();
+        assert_eq!(session.score.total, cascade_points);
+        assert_eq!(session.score.cascade_depth, 0);
+
+        // the same seed and move play out the same, step by step:
+        let mut stepped = GameSession::new(corpus, settings(), 3).unwrap();
+        stepped.apply_move(mv).unwrap();
+        let mut steps = vec![stepped.resolve_step()];
+        while !steps.last().unwrap().is_final() {
+            steps.push(stepped.resolve_step());
+        }
+        assert_eq!(steps, timeline.steps);
+        assert_eq!(stepped.letterfield, session.letterfield);
+
+        // restoring a snapshot goes back to before the move:
+        session.restore(before.clone());
+        assert_eq!(session.snapshot(), before);
+    }
+
+    #[test]
+    fn session_snapshot_during_drag() {
+        let corpus = Corpus::from_words(["Cat", "Tomb", "Atom", "Act", "Mob", "Tab"]);
+        let mut session = GameSession::new(corpus, settings(), 3).unwrap();
+        let before = session.snapshot();
+        let from = Int2 { x: 0, y: 1 };
+        let to = Int2 { x: 3, y: 1 };
+        // the tile is dragged over the letterfield, the move is not played yet:
+        session.letterfield.move_letter(from, to).unwrap();
+        session.letterfield.move_letter(to, to.with_x(4)).unwrap();
+        assert_ne!(session.letterfield, before.letterfield);
+
+        let saved = session.snapshot_before_drag(from, to.with_x(4)).unwrap();
+        assert_eq!(saved, before);
+        // resuming the saved game does not score the drag:
+        let mut resumed = GameSession::resume(session.corpus.clone(), settings(), 3, saved);
+        resumed.resolve();
+        assert_eq!(resumed.snapshot(), before);
+    }
+
+    #[test]
+    fn session_errors() {
+        let corpus = Corpus::from_words(["Cat"]);
+        let mut session = GameSession::new(corpus, settings(), 0).unwrap();
+        let out_of_bounds = Move {
+            from: Int2 { x: 0, y: 0 },
+            to: Int2 { x: 0, y: 9 },
+        };
+        assert!(matches!(
+            session.apply_move(out_of_bounds),
+            Err(SessionError::Move(_))
+        ));
+        assert_eq!(session.moves, 0);
+
+        session.game_over = true;
+        let mv = Move {
+            from: Int2 { x: 0, y: 0 },
+            to: Int2 { x: 0, y: 1 },
+        };
+        assert!(matches!(
+            session.apply_move(mv),
+            Err(SessionError::GameOver)
+        ));
+
+        // every refill of an all A board creates new matches:
+        let endless = GameSession::new(Corpus::from_words(["Aaa"]), settings(), 0);
+        assert!(matches!(
+            endless,
+            Err(SessionError::Generation(
+                GenerationError::AttemptsExhausted { .. }
+            ))
+        ));
+
+        let empty = GameSettings {
+            width: 0,
+            ..settings()
+        };
+        assert!(matches!(
+            GameSession::new(Corpus::from_words(["Cat"]), empty, 0),
+            Err(SessionError::Generation(
+                GenerationError::EmptyLetterfield { width: 0, .. }
+            ))
+        ));
+    }
+}
@@ -0,0 +1,241 @@
+use rand::Rng;
+use rayon::prelude::*;
+
+use super::{
+    corpus::Corpus,
+    letter_source::LetterSource,
+    letterfield::{GenerationConstraints, Letterfield, LetterfieldResolve, LineMatches, Move},
+    rules::RuleSet,
+    score::{ScoreBreakdown, ScoreConfig},
+};
+
+/// the result of simulating a move including the full cascade after it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RankedMove {
+    pub mv: Move,
+    /// matches created by the move itself
+    pub immediate_matches: usize,
+    /// points of the move and all cascades after it
+    pub score: u64,
+    /// number of resolves with matches, 0 if the move does not create a word
+    pub cascade_depth: u32,
+}
+
+impl Letterfield {
+    /// every tile to every other cell of its row or column.
+    pub fn legal_moves(&self) -> Vec<Move> {
+        let (width, height) = self.dimensions();
+        let mut moves: Vec<Move> = vec![];
+        for (from, _) in self.iter() {
+            for x in (0..width).filter(|x| *x != from.x) {
+                moves.push(Move {
+                    from,
+                    to: from.with_x(x),
+                });
+            }
+            for y in (0..height).filter(|y| *y != from.y) {
+                moves.push(Move {
+                    from,
+                    to: from.with_y(y),
+                });
+            }
+        }
+        moves
+    }
+
+    /// simulates all legal moves in parallel and ranks them, best first:
+    /// by the number of matches the move creates, then by the points including cascades.
+    ///
+    /// every simulation draws its refills from its own clones of `letters` and `rng`.
+    pub fn rank_moves<S, R>(
+        &self,
+        corpus: &Corpus,
+        rules: &RuleSet,
+        score_config: &ScoreConfig,
+        constraints: &GenerationConstraints,
+        letters: &S,
+        rng: &R,
+    ) -> Vec<RankedMove>
+    where
+        S: LetterSource + Clone + Send + Sync,
+        R: Rng + Clone + Send + Sync,
+    {
+        let line_matches = self.line_matches(corpus, rules);
+        let mut ranked: Vec<RankedMove> = self
+            .legal_moves()
+            .into_par_iter()
+            .map(
+                |mv| match self.move_creating_word(mv, &line_matches, corpus, rules) {
+                    Some(mut letterfield) => {
+                        let resolves = settled_resolves(
+                            &mut letterfield,
+                            corpus,
+                            rules,
+                            constraints,
+                            &mut letters.clone(),
+                            &mut rng.clone(),
+                        );
+                        ranked_move(mv, &resolves, corpus, score_config)
+                    }
+                    None => ranked_move(mv, &[], corpus, score_config),
+                },
+            )
+            .collect();
+        // stable, so equally good moves keep the order of `legal_moves`:
+        ranked.sort_by(|a, b| {
+            b.immediate_matches
+                .cmp(&a.immediate_matches)
+                .then(b.score.cmp(&a.score))
+        });
+        ranked
+    }
+
+    /// the legal moves that create at least one word, without simulating the cascades after them.
+    pub fn word_moves(&self, corpus: &Corpus, rules: &RuleSet) -> Vec<Move> {
+        let line_matches = self.line_matches(corpus, rules);
+        self.legal_moves()
+            .into_par_iter()
+            .filter(|mv| {
+                self.move_creating_word(*mv, &line_matches, corpus, rules)
+                    .is_some()
+            })
+            .collect()
+    }
+
+    /// true if no move creates a word anymore.
+    pub fn is_dead(&self, corpus: &Corpus, rules: &RuleSet) -> bool {
+        let line_matches = self.line_matches(corpus, rules);
+        !self.legal_moves().into_par_iter().any(|mv| {
+            self.move_creating_word(mv, &line_matches, corpus, rules)
+                .is_some()
+        })
+    }
+
+    /// the letterfield after the move, if the move is valid and creates a word.
+    ///
+    /// most moves create no word, rescanning only the lines they touch rules them out quickly.
+    fn move_creating_word(
+        &self,
+        mv: Move,
+        line_matches: &LineMatches,
+        corpus: &Corpus,
+        rules: &RuleSet,
+    ) -> Option<Letterfield> {
+        let mut letterfield = self.clone();
+        letterfield.move_letter(mv.from, mv.to).ok()?;
+        let mut line_matches = line_matches.clone();
+        line_matches.update_after_move(&letterfield, corpus, rules, mv.from, mv.to);
+        if line_matches.matches().is_empty() {
+            None
+        } else {
+            Some(letterfield)
+        }
+    }
+
+    /// the best move that creates at least one word, None if there is no such move.
+    pub fn hint<S, R>(
+        &self,
+        corpus: &Corpus,
+        rules: &RuleSet,
+        score_config: &ScoreConfig,
+        constraints: &GenerationConstraints,
+        letters: &S,
+        rng: &R,
+    ) -> Option<RankedMove>
+    where
+        S: LetterSource + Clone + Send + Sync,
+        R: Rng + Clone + Send + Sync,
+    {
+        self.rank_moves(corpus, rules, score_config, constraints, letters, rng)
+            .into_iter()
+            .next()
+            .filter(|m| m.immediate_matches > 0)
+    }
+}
+
+/// the resolves of the cascade after a move. a cascade that never ends ends the game,
+/// so such a move is ranked like a move that creates no word.
+fn settled_resolves(
+    letterfield: &mut Letterfield,
+    corpus: &Corpus,
+    rules: &RuleSet,
+    constraints: &GenerationConstraints,
+    letters: &mut impl LetterSource,
+    rng: &mut impl Rng,
+) -> Vec<LetterfieldResolve> {
+    letterfield
+        .settle(corpus, rules, constraints, letters, rng)
+        .unwrap_or_default()
+}
+
+fn ranked_move(
+    mv: Move,
+    resolves: &[LetterfieldResolve],
+    corpus: &Corpus,
+    score_config: &ScoreConfig,
+) -> RankedMove {
+    let score = resolves
+        .iter()
+        .zip(1..)
+        .map(|(resolve, depth)| ScoreBreakdown::new(resolve, corpus, score_config, depth).total)
+        .sum();
+    RankedMove {
+        mv,
+        immediate_matches: resolves.first().map(|r| r.matches.len()).unwrap_or(0),
+        score,
+        cascade_depth: resolves.len() as u32,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use crate::models::{
+        array2d::Int2,
+        corpus::Corpus,
+        letter_source::IndependentLetters,
+        letterfield::{GenerationConstraints, Letterfield, Move},
+        rules::RuleSet,
+        score::ScoreConfig,
+    };
+
+    #[test]
+    fn solver_ranks_word_creating_moves_first() {
+        let corpus = Corpus::from_words(["Cat"]);
+        let rules = RuleSet::default();
+        let config = ScoreConfig::default();
+        let letterfield: Letterfield = "CAK\nDET".to_string().try_into().unwrap();
+        assert_eq!(letterfield.legal_moves().len(), 6 * 3);
+
+        let letters = IndependentLetters::new(&corpus).unwrap();
+        let rng = ChaCha8Rng::seed_from_u64(0);
+        let constraints = GenerationConstraints::default();
+        let ranked = letterfield.rank_moves(&corpus, &rules, &config, &constraints, &letters, &rng);
+        assert_eq!(ranked.len(), 6 * 3);
+        // moving the K down or the T up both create CAT in the top row:
+        let best: Vec<Move> = ranked[..2].iter().map(|m| m.mv).collect();
+        assert_eq!(
+            best,
+            vec![
+                Move {
+                    from: Int2 { x: 2, y: 0 },
+                    to: Int2 { x: 2, y: 1 }
+                },
+                Move {
+                    from: Int2 { x: 2, y: 1 },
+                    to: Int2 { x: 2, y: 0 }
+                },
+            ]
+        );
+        assert_eq!(ranked[0].immediate_matches, 1);
+        assert!(ranked[0].score > 0);
+        assert_eq!(ranked[0].score, ranked[1].score);
+        assert_eq!(ranked[2].immediate_matches, 0);
+        assert_eq!(
+            letterfield.hint(&corpus, &rules, &config, &constraints, &letters, &rng),
+            Some(ranked[0].clone())
+        );
+    }
+}
@@ -0,0 +1,51 @@
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// NFC normalized upper case, the form of all words in a corpus and all letters on the board.
+///
+/// ß becomes ẞ instead of SS, such that words keep their number of letters.
+pub fn uppercase(s: &str) -> String {
+    let mut upper = String::with_capacity(s.len());
+    for c in s.nfc() {
+        match c {
+            'ß' => upper.push('ẞ'),
+            c => upper.extend(c.to_uppercase()),
+        }
+    }
+    // some upper case letters have no precomposed form, e.g. J with caron:
+    upper.nfc().collect()
+}
+
+/// removes accents, umlauts and other combining marks: É becomes E, Ä becomes A.
+pub fn fold_diacritics(s: &str) -> String {
+    s.nfd().filter(|c| !is_combining_mark(*c)).nfc().collect()
+}
+
+/// the number of letters as the player sees them (grapheme clusters), not bytes or chars.
+pub fn grapheme_len(s: &str) -> usize {
+    s.graphemes(true).count()
+}
+
+/// true if every letter of the NFC normalized `s` is a single char and can be put on a tile.
+pub fn fits_on_tiles(s: &str) -> bool {
+    s.graphemes(true).all(|g| g.chars().count() == 1)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::models::text::{fits_on_tiles, fold_diacritics, grapheme_len, uppercase};
+
+    #[test]
+    fn text_unicode_handling() {
+        assert_eq!(uppercase("straße"), "STRAẞE");
+        // e + combining acute accent is composed to É:
+        assert_eq!(uppercase("cafe\u{301}"), "CAFÉ");
+        assert_eq!(uppercase("café").chars().count(), 4);
+        assert_eq!(grapheme_len("cafe\u{301}"), 4);
+        assert_eq!(grapheme_len("Öl"), 2);
+        assert_eq!(fold_diacritics("ÉCOLE ÄRGER"), "ECOLE ARGER");
+        assert!(fits_on_tiles("STRAẞE"));
+        // no precomposed form of J with caron:
+        assert!(!fits_on_tiles(&uppercase("ǰa")));
+    }
+}
@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+
+/// prefix tree over the words of a corpus.
+///
+/// nodes are stored in a flat vec and refer to each other by index, the root is always node 0.
+#[derive(Debug, Clone)]
+pub struct Trie {
+    nodes: Vec<TrieNode>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct TrieNode {
+    children: HashMap<char, usize>,
+    is_word: bool,
+}
+
+impl Default for Trie {
+    fn default() -> Self {
+        Self {
+            nodes: vec![TrieNode::default()],
+        }
+    }
+}
+
+impl Trie {
+    pub const ROOT: usize = 0;
+
+    pub fn insert(&mut self, word: &str) {
+        let mut node = Self::ROOT;
+        for c in word.chars() {
+            node = match self.nodes[node].children.get(&c) {
+                Some(child) => *child,
+                None => {
+                    let child = self.nodes.len();
+                    self.nodes.push(TrieNode::default());
+                    self.nodes[node].children.insert(c, child);
+                    child
+                }
+            };
+        }
+        self.nodes[node].is_word = true;
+    }
+
+    /// the node reached from `node` by appending `c`, None if no word continues like this.
+    pub fn step(&self, node: usize, c: char) -> Option<usize> {
+        self.nodes[node].children.get(&c).copied()
+    }
+
+    pub fn is_word(&self, node: usize) -> bool {
+        self.nodes[node].is_word
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        let mut node = Self::ROOT;
+        for c in word.chars() {
+            match self.step(node, c) {
+                Some(child) => node = child,
+                None => return false,
+            }
+        }
+        self.is_word(node)
+    }
+}
+
+impl<S: AsRef<str>> FromIterator<S> for Trie {
+    fn from_iter<T: IntoIterator<Item = S>>(words: T) -> Self {
+        let mut trie = Trie::default();
+        for w in words {
+            trie.insert(w.as_ref());
+        }
+        trie
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::models::trie::Trie;
+
+    #[test]
+    fn trie_contains_only_full_words() {
+        let trie: Trie = ["CAT", "CATS", "ATOM"].into_iter().collect();
+        assert!(trie.contains("CAT"));
+        assert!(trie.contains("CATS"));
+        assert!(trie.contains("ATOM"));
+        // prefixes are not words:
+        assert!(!trie.contains("CA"));
+        assert!(!trie.contains("ATO"));
+        assert!(!trie.contains("DOG"));
+        assert!(!trie.contains(""));
+    }
+}
@@ -0,0 +1,124 @@
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+
+use crate::{
+    constants::{
+        CORPUS_PATH, LETTERFIELD_SIZE, LETTERTILE_TEXT_SIZE, LETTER_SOURCE, MIN_WORD_LENGTH,
+        MULTI_LETTER_TILES, RARITY_TIER_PATHS, REPLAY_DIR, SAVEGAME_PATH,
+    },
+    events::EventsPlugin,
+    models::{
+        corpus::CorpusSource, letter::Letter, letterfield::GenerationConstraints, rules::RuleSet,
+        score::ScoreConfig, session::GameSettings,
+    },
+    resources::ResourcesPlugin,
+    save::SavePlugin,
+    state::{IngameState, StateSystemsPlugin},
+    systems::{
+        debug_ui::DebugUiSystemsPlugin, game_over::GameOverSystemsPlugin,
+        game_ui::GameUiSystemsPlugin, history::HistorySystemsPlugin, input::InputSystemsPlugin,
+        replay::ReplaySystemsPlugin, score_popups::ScorePopupSystemsPlugin,
+        setup::SetupSystemsPlugin,
+    },
+};
+
+/// the whole game, to add to an app with a window:
+/// `app.add_plugins(LetterfrontPlugin { config })`
+#[derive(Debug, Clone, Default)]
+pub struct LetterfrontPlugin {
+    pub config: LetterfrontConfig,
+}
+
+impl Plugin for LetterfrontPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.config.clone())
+            .add_state::<IngameState>()
+            .add_plugins(EventsPlugin)
+            .add_plugins(ResourcesPlugin)
+            .add_plugins(StateSystemsPlugin)
+            .add_plugins(SetupSystemsPlugin)
+            .add_plugins(InputSystemsPlugin)
+            .add_plugins(HistorySystemsPlugin)
+            .add_plugins(ReplaySystemsPlugin)
+            .add_plugins(GameUiSystemsPlugin)
+            .add_plugins(GameOverSystemsPlugin)
+            .add_plugins(ScorePopupSystemsPlugin)
+            .add_plugins(SavePlugin);
+        if self.config.debug_ui {
+            app.add_plugins(DebugUiSystemsPlugin);
+        }
+    }
+}
+
+/// how the game is set up, read once at startup.
+#[derive(Debug, Clone, Resource)]
+pub struct LetterfrontConfig {
+    /// the words of new games, replays and saved games bring their own
+    pub corpus: CorpusSource,
+    /// board dimensions, rules and scoring of new games
+    pub settings: GameSettings,
+    /// where the game is saved on exit and resumed from, None to always start a new game
+    pub savegame_path: Option<PathBuf>,
+    /// every game is written to `<seed>.json` in here, None to not record replays
+    pub replay_dir: Option<PathBuf>,
+    /// the seed of the first new game, a random one if None
+    pub seed: Option<u64>,
+    /// a replay to play back instead of the saved game or a new game
+    pub replay_path: Option<PathBuf>,
+    pub theme: Theme,
+    /// an egui window with the state, the score breakdown, the letterfield and the matches
+    pub debug_ui: bool,
+}
+
+impl Default for LetterfrontConfig {
+    fn default() -> Self {
+        Self {
+            corpus: CorpusSource {
+                rarity_tier_paths: Some((RARITY_TIER_PATHS.0.into(), RARITY_TIER_PATHS.1.into())),
+                multi_letter_tiles: MULTI_LETTER_TILES
+                    .iter()
+                    .map(|tile| Letter::try_from(*tile).unwrap())
+                    .collect(),
+                ..CorpusSource::new(CORPUS_PATH)
+            },
+            settings: GameSettings {
+                rules: RuleSet::default().with_min_word_length(MIN_WORD_LENGTH),
+                score_config: ScoreConfig::default(),
+                letter_source: LETTER_SOURCE,
+                constraints: GenerationConstraints::default(),
+                width: LETTERFIELD_SIZE.x,
+                height: LETTERFIELD_SIZE.y,
+            },
+            savegame_path: Some(SAVEGAME_PATH.into()),
+            replay_dir: Some(REPLAY_DIR.into()),
+            seed: None,
+            replay_path: None,
+            theme: Theme::default(),
+            debug_ui: true,
+        }
+    }
+}
+
+/// the look of the tiles, the paths are relative to the asset folder.
+#[derive(Debug, Clone)]
+pub struct Theme {
+    pub font: String,
+    pub letter_size: f32,
+    pub letter_color: Color,
+    pub tile_texture: String,
+    /// tint of the tile under the cursor
+    pub hover_color: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            font: "NotoSerif-Bold.ttf".to_string(),
+            letter_size: LETTERTILE_TEXT_SIZE,
+            letter_color: Color::BLACK,
+            tile_texture: "tile.png".to_string(),
+            hover_color: Color::VIOLET,
+        }
+    }
+}
@@ -1,40 +1,69 @@
+use std::{fmt::Display, path::PathBuf};
+
 use bevy::{
     prelude::*,
     text::TextStyle,
     utils::{HashMap, HashSet},
 };
+use rand::{random, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 
 use crate::{
-    constants::{LETTERFIELD_SIZE, LETTERTILE_TEXT_SIZE, MIN_WORD_LENGTH},
+    constants::REPLAY_MOVE_INTERVAL,
     models::{
         array2d::Int2,
-        corpus::Corpus,
-        letterfield::{self, Letterfield, WordMatch},
+        corpus::{Corpus, CorpusError, CorpusSource},
+        history::MoveHistory,
+        letterfield::{LineMatches, WordMatch},
+        replay::{Replay, ReplayStep},
+        session::{GameSession, ResolveStep, SessionError, SessionState},
+        solver::RankedMove,
     },
+    plugin::LetterfrontConfig,
+    save::SaveGame,
+    state::IngameState,
 };
 
 pub struct ResourcesPlugin;
 
 impl Plugin for ResourcesPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
-        app.init_resource::<GrabbedLetterResource>()
+        app.init_resource::<LetterfrontConfig>()
+            .init_resource::<GrabbedLetterResource>()
             .init_resource::<WordMatchesResource>()
+            .init_resource::<HintResource>()
+            .init_resource::<MoveHistoryResource>()
+            .init_resource::<ReplayResource>()
             .init_resource::<CursorState>()
-            .add_systems(PreStartup, load_corpus_and_init_letterfield)
-            .add_systems(PreStartup, load_text_styles);
+            .add_systems(PreStartup, load_corpus_and_start_session)
+            .add_systems(PreStartup, load_theme_assets);
     }
 }
 
+/// the loaded assets of the `Theme`.
 #[derive(Debug, Resource)]
-pub struct FontAssets {
+pub struct ThemeAssets {
     pub tile_text_style: TextStyle,
+    pub tile_texture: Handle<Image>,
 }
 
+/// the game: letterfield, corpus, score and the randomness of the board. the systems only animate what it does.
 #[derive(Debug, Clone, Resource)]
-pub struct CorpusResource(pub Corpus);
+pub struct GameSessionResource(pub GameSession);
 
+/// the randomness of visual effects, on a separate stream such that animations never change the board.
 #[derive(Debug, Clone, Resource)]
-pub struct LetterfieldResource(pub Letterfield);
+pub struct RngResource {
+    pub effects: ChaCha8Rng,
+}
+
+impl RngResource {
+    pub fn from_seed(seed: u64) -> Self {
+        let mut effects = ChaCha8Rng::seed_from_u64(seed);
+        effects.set_stream(1);
+        Self { effects }
+    }
+}
 
 #[derive(Debug, Clone, Resource, Default)]
 pub struct WordMatchesResource {
@@ -62,6 +91,63 @@ impl WordMatchesResource {
     }
 }
 
+/// the move suggested by the solver, only valid for the letterfield it was computed on.
+#[derive(Debug, Clone, Resource, Default)]
+pub struct HintResource(pub Option<RankedMove>);
+
+/// the moves of the game for undo and redo.
+#[derive(Debug, Clone, Resource, Default)]
+pub struct MoveHistoryResource(pub MoveHistory<SessionState>);
+
+/// where the words of this game come from, for its replay and saved game.
+#[derive(Debug, Clone, Resource)]
+pub struct CorpusSourceResource(pub CorpusSource);
+
+/// the replay of this game up to the start of the session, written to the `replay_dir` on exit.
+///
+/// None while a replay is played back and for saved games that can not be replayed.
+#[derive(Debug, Clone, Resource, Default)]
+pub struct ReplayResource(pub Option<Replay>);
+
+impl ReplayResource {
+    /// the replay including the moves of this session that were not undone.
+    pub fn with_history(&self, history: &MoveHistoryResource) -> Option<Replay> {
+        let mut replay = self.0.clone()?;
+        replay.steps.extend(history.0.entries().iter().map(|entry| {
+            ReplayStep::new(
+                entry.mv,
+                entry.after.state.score.total,
+                &entry.after.letterfield,
+            )
+        }));
+        Some(replay)
+    }
+}
+
+/// a replay that is played back instead of a new game, see `LetterfrontConfig::replay_path`.
+#[derive(Debug, Clone, Resource)]
+pub struct ReplayPlaybackResource {
+    pub replay: Replay,
+    /// index of the next move to play
+    pub next_step: usize,
+    /// index of the step whose result is compared once the letterfield is settled
+    pub pending_check: Option<usize>,
+    pub divergences: usize,
+    pub timer: Timer,
+}
+
+impl ReplayPlaybackResource {
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            replay,
+            next_step: 0,
+            pending_check: None,
+            divergences: 0,
+            timer: Timer::new(REPLAY_MOVE_INTERVAL, TimerMode::Repeating),
+        }
+    }
+}
+
 /// u32 is the id in terms of the letterfield
 #[derive(Debug, Clone, Resource, Default)]
 pub struct GrabbedLetterResource(pub Option<GrabbedLetter>);
@@ -73,6 +159,8 @@ pub struct GrabbedLetter {
     pub original_char_pos: Int2,
     pub new_char_pos: Int2,
     pub offset_to_cursor: Vec2,
+    /// matches of the letterfield while dragging, created on the first move of the grab.
+    pub line_matches: Option<LineMatches>,
 }
 
 #[derive(Debug, Clone, Resource, Default)]
@@ -87,24 +175,176 @@ pub struct CursorState {
 // #[derive(Debug, Clone, Resource, Default)]
 // pub struct LetterMatchesResource {}
 
-fn load_text_styles(mut commands: Commands, asset_server: Res<AssetServer>) {
-    let font = asset_server.load("NotoSerif-Bold.ttf");
+fn load_theme_assets(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    config: Res<LetterfrontConfig>,
+) {
+    let theme = &config.theme;
+    let font = asset_server.load(&theme.font);
     let tile_text_style = TextStyle {
         font: font.clone(),
-        font_size: LETTERTILE_TEXT_SIZE,
-        color: Color::BLACK,
+        font_size: theme.letter_size,
+        color: theme.letter_color,
+    };
+    commands.insert_resource(ThemeAssets {
+        tile_text_style,
+        tile_texture: asset_server.load(&theme.tile_texture),
+    });
+}
+
+/// why the game could not be started.
+#[derive(Debug)]
+pub enum StartupError {
+    /// the replay to play back could not be read
+    Replay {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// the words of the game could not be loaded
+    Corpus { path: PathBuf, source: CorpusError },
+    /// no letterfield could be created with the settings
+    Session(SessionError),
+}
+
+impl Display for StartupError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StartupError::Replay { path, source } => {
+                write!(f, "could not read the replay {}: {source}", path.display())
+            }
+            StartupError::Corpus { path, source } => {
+                write!(f, "could not load the corpus {}: {source}", path.display())
+            }
+            StartupError::Session(err) => write!(f, "could not start the game: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for StartupError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            StartupError::Replay { source, .. } => Some(source),
+            StartupError::Corpus { source, .. } => Some(source),
+            StartupError::Session(err) => Some(err),
+        }
+    }
+}
+
+/// the error the game could not be started with. there is no `GameSessionResource` then.
+#[derive(Debug, Resource)]
+pub struct StartupErrorResource(pub StartupError);
+
+/// the replay at the `replay_path` of the config, if it is set.
+fn replay_to_play_back(config: &LetterfrontConfig) -> Result<Option<Replay>, StartupError> {
+    let Some(path) = &config.replay_path else {
+        return Ok(None);
     };
-    commands.insert_resource(FontAssets { tile_text_style });
+    match Replay::read(path) {
+        Ok(replay) => Ok(Some(replay)),
+        Err(source) => Err(StartupError::Replay {
+            path: path.clone(),
+            source,
+        }),
+    }
+}
+
+/// starts the game that `replay` records.
+fn start_session(replay: &Replay, corpus: Corpus) -> Result<GameSession, StartupError> {
+    let session = GameSession::new(corpus, replay.settings.clone(), replay.seed)
+        .map_err(StartupError::Session)?;
+    println!("Letterfield created from seed {}", session.seed);
+    Ok(session)
 }
 
 /// todo later: put this in loading stage
-fn load_corpus_and_init_letterfield(mut commands: Commands) {
-    let corpus = Corpus::from_txt_file("assets/3esl.txt", MIN_WORD_LENGTH).unwrap();
-    let (letterfield, tries) =
-        Letterfield::random_with_no_matches(LETTERFIELD_SIZE.x, LETTERFIELD_SIZE.y, &corpus);
-    println!("Letterfield created with {tries} tries");
-    commands.insert_resource(CorpusResource(corpus));
-    commands.insert_resource(LetterfieldResource(letterfield));
+fn load_corpus_and_start_session(
+    mut commands: Commands,
+    config: Res<LetterfrontConfig>,
+    mut next_state: ResMut<NextState<IngameState>>,
+) {
+    // the game is not started, the app it is embedded in keeps running:
+    if let Err(err) = start_game(&config, &mut commands, &mut next_state) {
+        println!("Could not start the game: {err}");
+        commands.insert_resource(StartupErrorResource(err));
+    }
+}
+
+/// a replay to play back, the saved game or a new game. the resources are only inserted if it succeeds.
+fn start_game(
+    config: &LetterfrontConfig,
+    commands: &mut Commands,
+    next_state: &mut NextState<IngameState>,
+) -> Result<(), StartupError> {
+    // a replay is played back with its own corpus and settings instead of the saved game:
+    let playback = replay_to_play_back(config)?;
+    let save_game = match (&playback, &config.savegame_path) {
+        (None, Some(path)) => match SaveGame::read(path) {
+            Ok(save_game) => Some(save_game),
+            Err(err) => {
+                if err.kind() != std::io::ErrorKind::NotFound {
+                    println!("Could not load saved game: {err}");
+                }
+                None
+            }
+        },
+        _ => None,
+    };
+    // a saved game continues with the corpus and settings it was started with, not the current config:
+    let (corpus_source, settings) = match (&playback, &save_game) {
+        (Some(replay), _) => (replay.corpus.clone(), replay.settings.clone()),
+        (None, Some(save_game)) => (save_game.corpus.clone(), save_game.settings.clone()),
+        (None, None) => (config.corpus.clone(), config.settings.clone()),
+    };
+    let corpus = corpus_source
+        .load(settings.rules.shortest_word_length())
+        .map_err(|source| StartupError::Corpus {
+            path: corpus_source.path.clone(),
+            source,
+        })?;
+    let (session, replay) = if let Some(replay) = playback {
+        let session = start_session(&replay, corpus)?;
+        println!("Playing back a replay with {} moves", replay.steps.len());
+        commands.insert_resource(ReplayPlaybackResource::new(replay));
+        (session, None)
+    } else if let Some(SaveGame {
+        seed,
+        game,
+        state,
+        replay,
+        ..
+    }) = save_game
+    {
+        let mut session = GameSession::resume(corpus, settings, seed, game);
+        // the game might have been quit during a move or a cascade, resolve the remaining matches:
+        if let Some(ResolveStep::GameOver(err)) = session.resolve().last() {
+            println!("Saved letterfield has no moves left: {err}");
+        }
+        println!("Resumed saved game (quit in state {state:?})");
+        if session.game_over {
+            next_state.set(IngameState::GameOver);
+        }
+        // the unfinished move is not in the replay, so it would not play out the same:
+        let replay = replay.filter(|_| state == IngameState::Inspect);
+        if replay.is_none() {
+            println!("The resumed game is not recorded in a replay");
+        }
+        (session, replay)
+    } else {
+        let replay = Replay {
+            seed: config.seed.unwrap_or_else(random),
+            corpus: corpus_source.clone(),
+            settings,
+            steps: vec![],
+        };
+        let session = start_session(&replay, corpus)?;
+        (session, Some(replay))
+    };
+    commands.insert_resource(RngResource::from_seed(session.seed));
+    commands.insert_resource(GameSessionResource(session));
+    commands.insert_resource(ReplayResource(replay));
+    commands.insert_resource(CorpusSourceResource(corpus_source));
 
     println!("loaded the corpus");
+    Ok(())
 }
@@ -0,0 +1,134 @@
+use std::path::Path;
+
+use bevy::{app::AppExit, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    models::{
+        corpus::CorpusSource,
+        history::Snapshot,
+        replay::Replay,
+        session::{GameSettings, SessionState},
+    },
+    plugin::LetterfrontConfig,
+    resources::{
+        CorpusSourceResource, GameSessionResource, GrabbedLetterResource, MoveHistoryResource,
+        ReplayPlaybackResource, ReplayResource,
+    },
+    state::IngameState,
+};
+
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Last,
+            save_on_exit.run_if(resource_exists::<GameSessionResource>()),
+        );
+    }
+}
+
+/// everything needed to continue a game after a restart.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveGame {
+    pub seed: u64,
+    /// the words the game is played with, the config might have changed since
+    pub corpus: CorpusSource,
+    /// the settings the game was started with
+    pub settings: GameSettings,
+    /// the letterfield, letters, randomness and score of the `GameSession`
+    pub game: Snapshot<SessionState>,
+    /// the state the game was quit in. if it is not `Inspect` the letterfield can still contain matches.
+    pub state: IngameState,
+    /// the game so far, the replay is continued after a restart
+    #[serde(default)]
+    pub replay: Option<Replay>,
+}
+
+impl SaveGame {
+    pub fn read(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let content = serde_json::to_string(self)?;
+        std::fs::write(path, content)
+    }
+}
+
+fn save_on_exit(
+    mut app_exit: EventReader<AppExit>,
+    session: Res<GameSessionResource>,
+    ingame_state: Res<State<IngameState>>,
+    replay: Res<ReplayResource>,
+    history: Res<MoveHistoryResource>,
+    playback: Option<Res<ReplayPlaybackResource>>,
+    config: Res<LetterfrontConfig>,
+    corpus_source: Res<CorpusSourceResource>,
+    grabbed_letter: Res<GrabbedLetterResource>,
+) {
+    if app_exit.iter().last().is_none() {
+        return;
+    }
+    if playback.is_some() {
+        // playing back a replay does not touch the saved game
+        return;
+    }
+    let replay = replay.with_history(&history);
+    if let Some(replay) = &replay {
+        write_replay(replay, &config);
+    }
+    let Some(savegame_path) = &config.savegame_path else {
+        return;
+    };
+    if *ingame_state.get() == IngameState::GameOver {
+        // the next launch starts a new game:
+        if let Err(err) = std::fs::remove_file(savegame_path) {
+            if err.kind() != std::io::ErrorKind::NotFound {
+                println!("Could not remove saved game: {err}");
+            }
+        }
+        return;
+    }
+    // a tile that is still dragged is put back, the game continues before the drag:
+    let (game, state) = match &grabbed_letter.0 {
+        Some(grabbed) => match session
+            .0
+            .snapshot_before_drag(grabbed.original_char_pos, grabbed.new_char_pos)
+        {
+            Ok(game) => (game, IngameState::Inspect),
+            Err(err) => {
+                println!("Could not save game: {err}");
+                return;
+            }
+        },
+        None => (session.0.snapshot(), ingame_state.get().clone()),
+    };
+    let save_game = SaveGame {
+        seed: session.0.seed,
+        corpus: corpus_source.0.clone(),
+        settings: session.0.settings.clone(),
+        game,
+        state,
+        replay,
+    };
+    match save_game.write(savegame_path) {
+        Ok(()) => println!("Saved game to {}", savegame_path.display()),
+        Err(err) => println!("Could not save game: {err}"),
+    }
+}
+
+/// writes the replay to the `replay_dir` of the config, named after its seed.
+pub fn write_replay(replay: &Replay, config: &LetterfrontConfig) {
+    let Some(replay_dir) = &config.replay_dir else {
+        return;
+    };
+    let path = replay_dir.join(format!("{}.json", replay.seed));
+    let result = std::fs::create_dir_all(replay_dir).and_then(|()| replay.write(&path));
+    match result {
+        Ok(()) => println!("Saved replay to {}", path.display()),
+        Err(err) => println!("Could not save replay: {err}"),
+    }
+}
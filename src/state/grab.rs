@@ -7,10 +7,13 @@ use bevy::{
 
 use crate::{
     components::{FadingLetter, FallingLetter, HoverableTile, LetterTile},
-    models::{array2d::Int2, letterfield},
+    models::{
+        array2d::Int2,
+        letterfield::{self, Move},
+    },
     resources::{
-        CorpusResource, CursorState, GrabbedLetterResource, LetterfieldResource,
-        WordMatchesResource,
+        CursorState, GameSessionResource, GrabbedLetterResource, MoveHistoryResource,
+        ReplayPlaybackResource, WordMatchesResource,
     },
     utils::{char_pos_to_world_pos, cursor_pos_to_grabbed_tile_pos, world_pos_to_char_pos},
 };
@@ -23,15 +26,22 @@ impl Plugin for IngameStateGrabPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
         app.add_systems(
             PreUpdate,
-            move_grabbed_letter_to_cursor.run_if(in_state(IngameState::Grab)),
+            move_grabbed_letter_to_cursor.run_if(
+                in_state(IngameState::Grab)
+                    .and_then(not(resource_exists::<ReplayPlaybackResource>())),
+            ),
         )
         .add_systems(
             PostUpdate,
-            exit_grabbed_state_if_not_mouse_pressed.run_if(in_state(IngameState::Grab)),
+            exit_grabbed_state_if_not_mouse_pressed.run_if(
+                in_state(IngameState::Grab)
+                    .and_then(not(resource_exists::<ReplayPlaybackResource>())),
+            ),
         )
         .add_systems(
             Update,
-            move_letter_tiles_to_correct_positions, // not only in grab state.
+            // not only in grab state:
+            move_letter_tiles_to_correct_positions.run_if(resource_exists::<GameSessionResource>()),
         )
         .add_systems(
             Update,
@@ -45,21 +55,42 @@ fn exit_grabbed_state_if_not_mouse_pressed(
     mut next_state: ResMut<NextState<IngameState>>,
     mut windows: Query<&mut Window>,
     mut grabbed_letter: ResMut<GrabbedLetterResource>,
-    mut word_matches: ResMut<WordMatchesResource>,
+    mut history: ResMut<MoveHistoryResource>,
+    mut session: ResMut<GameSessionResource>,
 ) {
     let mut window = windows.get_single_mut().unwrap();
 
     if !cursor_state.pressed {
         // todo: update dropped item???
-        grabbed_letter.0 = None;
+        let mut moved = false;
+        if let Some(grabbed) = grabbed_letter.0.take() {
+            let mv = Move {
+                from: grabbed.original_char_pos,
+                to: grabbed.new_char_pos,
+            };
+            // the letterfield already shows the move while dragging,
+            // moving the letter back gives the state to play the move on:
+            let session = &mut session.0;
+            if mv.from != mv.to && session.letterfield.move_letter(mv.to, mv.from).is_ok() {
+                let before = session.snapshot();
+                match session.apply_move(mv) {
+                    Ok(_) => {
+                        history.0.begin_move(mv, before);
+                        moved = true;
+                    }
+                    Err(err) => println!("Could not play the move: {err}"),
+                }
+            }
+        }
         window.cursor.visible = true;
         // window.cursor.grab_mode = CursorGrabMode::Locked;
         // window.set_cursor_position(Some(Vec2 { x: 0., y: 0. })); // todo!() needle
 
-        if word_matches.matches.is_empty() {
-            next_state.set(IngameState::Inspect);
-        } else {
+        if moved {
+            // resolves the matches, or reshuffles if the move left no move that creates a word:
             next_state.set(IngameState::Resolve);
+        } else {
+            next_state.set(IngameState::Inspect);
         }
     }
 }
@@ -68,13 +99,13 @@ fn move_grabbed_letter_to_cursor(
     mut cursor_state: ResMut<CursorState>,
     mut grabbed_letter: ResMut<GrabbedLetterResource>,
     mut tiles: Query<(&mut Transform, &mut LetterTile), With<HoverableTile>>,
-    mut letterfield: ResMut<LetterfieldResource>,
+    mut session: ResMut<GameSessionResource>,
     mut word_matches: ResMut<WordMatchesResource>,
-    corpus: Res<CorpusResource>,
 ) {
     let Some(grabbed_letter) = &mut grabbed_letter.0 else {
         return;
     };
+    let session = &mut session.0;
 
     // let word_pos_rounded = char_pos_to_world_pos(
     //     world_pos_to_char_pos(
@@ -88,8 +119,8 @@ fn move_grabbed_letter_to_cursor(
 
     let (grabbed_tile_pos, new_char_pos) = cursor_pos_to_grabbed_tile_pos(
         cursor_state.world_pos,
-        letterfield.0.width(),
-        letterfield.0.height(),
+        session.letterfield.width(),
+        session.letterfield.height(),
         grabbed_letter.original_char_pos,
         grabbed_letter.new_char_pos,
     );
@@ -101,23 +132,39 @@ fn move_grabbed_letter_to_cursor(
     // check if the grabbed letter has a new char pos:
     if grabbed_letter.new_char_pos != new_char_pos {
         let old_char_pos = grabbed_letter.new_char_pos;
-        grabbed_letter.new_char_pos = new_char_pos;
+        let line_matches = grabbed_letter.line_matches.get_or_insert_with(|| {
+            session
+                .letterfield
+                .line_matches(&session.corpus, &session.settings.rules)
+        });
         // move the char in the lettergrid:
-        letterfield.0.move_letter(old_char_pos, new_char_pos);
-        // todo: needle recalculate matches
-        word_matches.set_matches(letterfield.0.find_word_matches(&corpus.0));
-        // update all letter_tiles (data only, not transform):
-        let tile_data_positions: HashMap<u32, Int2> = letterfield
-            .0
+        let outcome = match session.letterfield.move_letter(old_char_pos, new_char_pos) {
+            Ok(outcome) => outcome,
+            Err(err) => {
+                println!("Could not move the grabbed letter: {err}");
+                return;
+            }
+        };
+        grabbed_letter.new_char_pos = new_char_pos;
+        // only rescan the lines touched by the move:
+        line_matches.update_after_move(
+            &session.letterfield,
+            &session.corpus,
+            &session.settings.rules,
+            old_char_pos,
+            new_char_pos,
+        );
+        word_matches.set_matches(line_matches.matches());
+        // update the letter_tiles that were shifted by the move (data only, not transform):
+        let shifted_positions: HashMap<u32, Int2> = outcome
+            .shifted_tiles
             .iter()
-            .map(|(pos, (id, _))| {
-                // dbg!(id, pos);
-                (id, pos)
-            })
+            .map(|tile| (tile.id, tile.to))
             .collect();
         for (_, mut letter_tile) in &mut tiles {
-            // dbg!(&letter_tile.id);
-            letter_tile.pos = tile_data_positions[&letter_tile.id];
+            if let Some(pos) = shifted_positions.get(&letter_tile.id) {
+                letter_tile.pos = *pos;
+            }
         }
     }
 }
@@ -125,10 +172,10 @@ fn move_grabbed_letter_to_cursor(
 fn move_letter_tiles_to_correct_positions(
     mut tiles: Query<(&mut Transform, &LetterTile), With<HoverableTile>>,
     mut grabbed_letter: ResMut<GrabbedLetterResource>,
-    letterfield: Res<LetterfieldResource>,
+    session: Res<GameSessionResource>,
     time: Res<Time>,
 ) {
-    let (w, h) = letterfield.0.dimensions();
+    let (w, h) = session.0.letterfield.dimensions();
     for (mut transform, letter_tile) in &mut tiles {
         let world_pos_target = char_pos_to_world_pos(letter_tile.pos, w, h).extend(2.0);
         const LERP_SPEED: f32 = 5.0;
@@ -3,7 +3,8 @@ use bevy::prelude::*;
 use crate::{
     components::{HoverableTile, LetterTile},
     constants::TILE_SIZE,
-    resources::{CursorState, GrabbedLetter, GrabbedLetterResource},
+    plugin::LetterfrontConfig,
+    resources::{CursorState, GrabbedLetter, GrabbedLetterResource, ReplayPlaybackResource},
 };
 
 use super::IngameState;
@@ -13,7 +14,10 @@ impl Plugin for IngameStateInspectPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
         app.add_systems(
             PreUpdate,
-            update_hover_state.run_if(in_state(IngameState::Inspect)),
+            update_hover_state.run_if(
+                in_state(IngameState::Inspect)
+                    .and_then(not(resource_exists::<ReplayPlaybackResource>())),
+            ),
         )
         .add_systems(Update, update_hover_colors);
     }
@@ -22,13 +26,14 @@ impl Plugin for IngameStateInspectPlugin {
 pub fn update_hover_colors(
     q_tiles: Query<(&HoverableTile, &Children)>,
     mut q_tile_sprites: Query<&mut Sprite>,
+    config: Res<LetterfrontConfig>,
 ) {
     // todo!(): lerp!
     for (hover_tile, children) in &q_tiles {
         for child in children {
             if let Ok(mut sprite) = q_tile_sprites.get_mut(*child) {
                 if hover_tile.hovered {
-                    sprite.color = Color::VIOLET;
+                    sprite.color = config.theme.hover_color;
                 } else {
                     sprite.color = Color::WHITE;
                 }
@@ -70,6 +75,7 @@ fn update_hover_state(
                     offset_to_cursor: cursor_state.world_pos - transform.translation.truncate(),
                     original_char_pos: letter_tile.pos,
                     new_char_pos: letter_tile.pos,
+                    line_matches: None,
                 });
                 window.cursor.visible = false;
                 next_state.set(IngameState::Grab);
@@ -5,17 +5,20 @@ pub mod resolve;
 use bevy::prelude::*;
 
 use bevy::prelude::States;
+use serde::{Deserialize, Serialize};
 
 use self::{
     grab::IngameStateGrabPlugin, inspect::IngameStateInspectPlugin,
     resolve::IngameStateResolvePlugin,
 };
 
-#[derive(Debug, Clone, Eq, PartialEq, Hash, States)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash, States, Serialize, Deserialize)]
 pub enum IngameState {
     Inspect,
     Grab,
     Resolve,
+    /// no move creates a word anymore and reshuffling did not help
+    GameOver,
 }
 
 impl Default for IngameState {
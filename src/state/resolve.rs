@@ -4,16 +4,20 @@ use bevy::{
     utils::{HashMap, HashSet},
     window::CursorGrabMode,
 };
-use rand::random;
 
 use crate::{
     components::{FadingLetter, FallingLetter, HoverableTile, LetterTile},
     constants::{GRAVITY_ACCELERATION, RESOLVE_DURATION},
+    events::MatchScoredEvent,
     models::{
         array2d::Int2,
         letterfield::{self, Letterfield, WordMatch},
+        session::ResolveStep,
+    },
+    resources::{
+        GameSessionResource, GrabbedLetterResource, MoveHistoryResource, RngResource, ThemeAssets,
+        WordMatchesResource,
     },
-    resources::{CorpusResource, FontAssets, LetterfieldResource, WordMatchesResource, GrabbedLetterResource},
     systems::setup::create_letter_tile,
     utils::{char_pos_to_world_pos, char_pos_to_world_pos_i, AnimationDriver},
 };
@@ -50,29 +54,55 @@ impl Plugin for IngameStateResolvePlugin {
 // pub struct EndResolving;
 
 pub fn start_resolving(
-    mut letterfield: ResMut<LetterfieldResource>,
-    corpus: Res<CorpusResource>,
+    mut session: ResMut<GameSessionResource>,
+    mut rng: ResMut<RngResource>,
+    mut history: ResMut<MoveHistoryResource>,
+    mut match_scored: EventWriter<MatchScoredEvent>,
     mut tiles: Query<(Entity, &mut LetterTile)>,
     mut commands: Commands,
-    font_assets: Res<FontAssets>,
-    asset_server: Res<AssetServer>,
+    theme_assets: Res<ThemeAssets>,
     mut next_state: ResMut<NextState<IngameState>>,
 ) {
     println!("start resolve");
-    let resolve = letterfield
-        .0
-        .find_word_matches_and_fill_spaces_randomly(&corpus.0);
-
-    if resolve.is_empty() {
-        println!("transition back to inspect");
-        next_state.set(IngameState::Inspect);
-        return;
-    } else {
-        // todo!() needle, add the matches to a score!!!
-        for m in resolve.matches {
-            println!("Match: {:?}", m.kind);
+    let session = &mut session.0;
+    // the session resolves, this only animates its steps:
+    let resolve = match session.resolve_step() {
+        ResolveStep::Cascade { resolve, score } => {
+            history.0.record_resolve(resolve.clone());
+            for m in &score.matches {
+                println!(
+                    "Match: {:?} ({:?}) +{} x{} chain x{} rarity",
+                    m.word_match.kind,
+                    m.word_match.overlap_policy,
+                    m.points,
+                    m.cascade_depth,
+                    m.rarity_multiplier
+                );
+                match_scored.send(MatchScoredEvent(m.clone()));
+            }
+            resolve
         }
-    }
+        ResolveStep::Reshuffled { tries } => {
+            // stay in the resolve state until the tiles arrived at their new positions
+            println!("dead letterfield, reshuffled with {tries} tries");
+            move_tiles_to_letterfield_positions(&session.letterfield, &mut tiles, &mut commands);
+            return;
+        }
+        ResolveStep::Settled => {
+            // the move and all of its cascades are done:
+            history.0.end_move(session.snapshot());
+            println!("transition back to inspect");
+            next_state.set(IngameState::Inspect);
+            return;
+        }
+        ResolveStep::GameOver(err) => {
+            println!("dead letterfield, game over: {err}");
+            history.0.end_move(session.snapshot());
+            next_state.set(IngameState::GameOver);
+            return;
+        }
+    };
+    let letterfield = &session.letterfield;
 
     // new letters get spawned in
     for (id, (pos, char)) in resolve.new_letters {
@@ -80,19 +110,18 @@ pub fn start_resolving(
 
         let start_world_pos = char_pos_to_world_pos_i(
             (pos.x as isize, pos.y as isize - 10),
-            letterfield.0.width(),
-            letterfield.0.height(),
+            letterfield.width(),
+            letterfield.height(),
         );
         let target_world_pos =
-            char_pos_to_world_pos(pos, letterfield.0.width(), letterfield.0.height());
+            char_pos_to_world_pos(pos, letterfield.width(), letterfield.height());
 
         create_letter_tile(
             id,
             char,
             pos,
-            &letterfield.0,
-            &font_assets,
-            &asset_server,
+            letterfield,
+            &theme_assets,
             &mut commands,
             FallingLetter {
                 start_world_pos,
@@ -111,9 +140,9 @@ pub fn start_resolving(
             // let entity fall
 
             let start_world_pos =
-                char_pos_to_world_pos(*from, letterfield.0.width(), letterfield.0.height());
+                char_pos_to_world_pos(*from, letterfield.width(), letterfield.height());
             let target_world_pos =
-                char_pos_to_world_pos(*to, letterfield.0.width(), letterfield.0.height());
+                char_pos_to_world_pos(*to, letterfield.width(), letterfield.height());
             commands
                 .entity(entity)
                 .remove::<HoverableTile>()
@@ -129,7 +158,7 @@ pub fn start_resolving(
             commands
                 .entity(entity)
                 .remove::<HoverableTile>()
-                .insert(FadingLetter::new());
+                .insert(FadingLetter::new(&mut rng.effects));
         }
     }
 }
@@ -139,11 +168,12 @@ pub fn animate_falling_tiles(
     time: Res<Time>,
     mut commands: Commands,
     // just for forwarding to resolve again:
-    letterfield: ResMut<LetterfieldResource>,
-    corpus: Res<CorpusResource>,
+    session: ResMut<GameSessionResource>,
+    rng: ResMut<RngResource>,
+    history: ResMut<MoveHistoryResource>,
+    match_scored: EventWriter<MatchScoredEvent>,
     tiles: Query<(Entity, &mut LetterTile)>,
-    font_assets: Res<FontAssets>,
-    asset_server: Res<AssetServer>,
+    theme_assets: Res<ThemeAssets>,
     next_state: ResMut<NextState<IngameState>>,
 ) {
     let mut all_finished = true;
@@ -164,17 +194,50 @@ pub fn animate_falling_tiles(
         // transition back to inspect state, or resolve again
         println!("transition all finished");
         start_resolving(
-            letterfield,
-            corpus,
+            session,
+            rng,
+            history,
+            match_scored,
             tiles,
             commands,
-            font_assets,
-            asset_server,
+            theme_assets,
             next_state,
         )
     }
 }
 
+/// lets every tile whose position in the letterfield changed fly there, e.g. after a reshuffle.
+fn move_tiles_to_letterfield_positions(
+    letterfield: &Letterfield,
+    tiles: &mut Query<(Entity, &mut LetterTile)>,
+    commands: &mut Commands,
+) {
+    let positions: HashMap<u32, Int2> =
+        letterfield.iter().map(|(pos, (id, _))| (id, pos)).collect();
+    for (entity, mut letter_tile) in tiles {
+        let Some(pos) = positions.get(&letter_tile.id) else {
+            continue;
+        };
+        if *pos == letter_tile.pos {
+            continue;
+        }
+        let start_world_pos =
+            char_pos_to_world_pos(letter_tile.pos, letterfield.width(), letterfield.height());
+        let target_world_pos =
+            char_pos_to_world_pos(*pos, letterfield.width(), letterfield.height());
+        letter_tile.pos = *pos;
+        commands
+            .entity(entity)
+            .remove::<HoverableTile>()
+            .insert(FallingLetter {
+                start_world_pos,
+                target_world_pos,
+                time: 0.0,
+                target_time: 1.0,
+            });
+    }
+}
+
 pub fn animate_fading_tiles(
     mut fading_tiles: Query<(Entity, &mut Transform, &mut FadingLetter, &Children)>,
     time: Res<Time>,
@@ -186,8 +249,8 @@ pub fn animate_fading_tiles(
         if finished {
             commands.entity(entity).despawn_recursive();
         }
-        for c in children{
-            if let Ok(mut sprite) = tile_sprites.get_mut(*c){
+        for c in children {
+            if let Ok(mut sprite) = tile_sprites.get_mut(*c) {
                 sprite.color = Color::AQUAMARINE;
             }
         }
@@ -0,0 +1,53 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiPlugin};
+
+use crate::{
+    resources::{GameSessionResource, WordMatchesResource},
+    state::IngameState,
+};
+
+/// an egui window with the state, the score breakdown of the last move, the letterfield and the matches.
+pub struct DebugUiSystemsPlugin;
+impl Plugin for DebugUiSystemsPlugin {
+    fn build(&self, app: &mut App) {
+        // the app the game is embedded in might use egui itself:
+        if !app.is_plugin_added::<EguiPlugin>() {
+            app.add_plugins(EguiPlugin);
+        }
+        app.add_systems(
+            Update,
+            egui_debug.run_if(resource_exists::<GameSessionResource>()),
+        );
+    }
+}
+
+fn egui_debug(
+    mut contexts: EguiContexts,
+    ingame_state: Res<State<IngameState>>,
+    session: Res<GameSessionResource>,
+    word_matches: Res<WordMatchesResource>,
+) {
+    let score = &session.0.score;
+
+    egui::Window::new("Info").show(contexts.ctx_mut(), |ui| {
+        ui.label(format!("IngameState: {:?}", ingame_state));
+        ui.label(format!(
+            "Score: {} after {} moves",
+            score.total, session.0.moves
+        ));
+        for m in &score.last.matches {
+            ui.label(format!(
+                "{:?}: {:?} x {} x {} x {} = {} (x{} chain)",
+                m.word_match.kind,
+                m.letter_points,
+                m.length_multiplier,
+                m.cascade_multiplier,
+                m.rarity_multiplier,
+                m.points,
+                m.cascade_depth
+            ));
+        }
+        ui.label(format!("Letterfield: \n {}", &session.0.letterfield));
+        ui.label(format!("Matches: \n {:?}", &word_matches));
+    });
+}
@@ -0,0 +1,123 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiPlugin};
+use rand::random;
+
+use crate::{
+    components::LetterTile,
+    events::{HistoryEvent, NewGameEvent},
+    models::{replay::Replay, session::GameSession},
+    plugin::LetterfrontConfig,
+    resources::{
+        CorpusSourceResource, GameSessionResource, GrabbedLetterResource, HintResource,
+        MoveHistoryResource, ReplayPlaybackResource, ReplayResource, RngResource, ThemeAssets,
+        WordMatchesResource,
+    },
+    save::write_replay,
+    state::IngameState,
+    systems::setup::spawn_letter_field_tiles,
+};
+
+/// the game over window and starting a new game from it.
+pub struct GameOverSystemsPlugin;
+impl Plugin for GameOverSystemsPlugin {
+    fn build(&self, app: &mut App) {
+        // the app the game is embedded in might use egui itself:
+        if !app.is_plugin_added::<EguiPlugin>() {
+            app.add_plugins(EguiPlugin);
+        }
+        app.add_systems(
+            Update,
+            show_game_over_window.run_if(in_state(IngameState::GameOver)),
+        )
+        .add_systems(
+            Update,
+            start_new_game.run_if(resource_exists::<GameSessionResource>()),
+        );
+    }
+}
+
+fn show_game_over_window(
+    mut contexts: EguiContexts,
+    session: Res<GameSessionResource>,
+    history: Res<MoveHistoryResource>,
+    mut history_events: EventWriter<HistoryEvent>,
+    mut new_game_events: EventWriter<NewGameEvent>,
+) {
+    egui::Window::new("Game over")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(contexts.ctx_mut(), |ui| {
+            ui.heading(format!("{} points", session.0.score.total));
+            ui.label(format!("The game ended after {} moves.", session.0.moves));
+            ui.horizontal(|ui| {
+                if ui.button("New game").clicked() {
+                    new_game_events.send(NewGameEvent);
+                }
+                if ui
+                    .add_enabled(history.0.can_undo(), egui::Button::new("Undo"))
+                    .clicked()
+                {
+                    history_events.send(HistoryEvent::Undo);
+                }
+            });
+        });
+}
+
+/// replaces the game with a new one with the same settings and a random seed.
+///
+/// the replay of the finished game is written first, a replay that was played back ends.
+fn start_new_game(
+    mut new_game_events: EventReader<NewGameEvent>,
+    mut session: ResMut<GameSessionResource>,
+    config: Res<LetterfrontConfig>,
+    corpus_source: Res<CorpusSourceResource>,
+    mut history: ResMut<MoveHistoryResource>,
+    mut replay: ResMut<ReplayResource>,
+    playback: Option<Res<ReplayPlaybackResource>>,
+    mut word_matches: ResMut<WordMatchesResource>,
+    mut hint: ResMut<HintResource>,
+    mut grabbed_letter: ResMut<GrabbedLetterResource>,
+    tiles: Query<Entity, With<LetterTile>>,
+    mut commands: Commands,
+    theme_assets: Res<ThemeAssets>,
+    mut next_state: ResMut<NextState<IngameState>>,
+) {
+    if new_game_events.iter().last().is_none() {
+        return;
+    }
+    let seed = random();
+    let new_session =
+        match GameSession::new(session.0.corpus.clone(), session.0.settings.clone(), seed) {
+            Ok(session) => session,
+            Err(err) => {
+                println!("Could not start a new game: {err}");
+                return;
+            }
+        };
+    println!("Letterfield created from seed {seed}");
+
+    if playback.is_some() {
+        commands.remove_resource::<ReplayPlaybackResource>();
+    } else if let Some(finished) = replay.with_history(&history) {
+        write_replay(&finished, &config);
+    }
+    replay.0 = Some(Replay {
+        seed,
+        corpus: corpus_source.0.clone(),
+        settings: new_session.settings.clone(),
+        steps: vec![],
+    });
+    history.0 = Default::default();
+    word_matches.clear();
+    hint.0 = None;
+    grabbed_letter.0 = None;
+
+    for entity in &tiles {
+        commands.entity(entity).despawn_recursive();
+    }
+    spawn_letter_field_tiles(&new_session.letterfield, &theme_assets, &mut commands);
+    commands.insert_resource(RngResource::from_seed(seed));
+    session.0 = new_session;
+    next_state.set(IngameState::Inspect);
+}
@@ -0,0 +1,78 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiPlugin};
+
+use crate::{
+    events::HistoryEvent,
+    resources::{GameSessionResource, HintResource, MoveHistoryResource, StartupErrorResource},
+};
+
+/// the score, undo, redo and hints for the player, and the error if the game could not be started.
+pub struct GameUiSystemsPlugin;
+impl Plugin for GameUiSystemsPlugin {
+    fn build(&self, app: &mut App) {
+        // the app the game is embedded in might use egui itself:
+        if !app.is_plugin_added::<EguiPlugin>() {
+            app.add_plugins(EguiPlugin);
+        }
+        app.add_systems(
+            Update,
+            show_game_window.run_if(resource_exists::<GameSessionResource>()),
+        )
+        .add_systems(
+            Update,
+            show_startup_error_window.run_if(resource_exists::<StartupErrorResource>()),
+        );
+    }
+}
+
+fn show_game_window(
+    mut contexts: EguiContexts,
+    session: Res<GameSessionResource>,
+    mut hint: ResMut<HintResource>,
+    history: Res<MoveHistoryResource>,
+    mut history_events: EventWriter<HistoryEvent>,
+) {
+    if session.is_changed() {
+        hint.0 = None;
+    }
+
+    egui::Window::new("Letterfront")
+        .resizable(false)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.heading(format!("{} points", session.0.score.total));
+            ui.label(format!("{} moves", session.0.moves));
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(history.0.can_undo(), egui::Button::new("Undo"))
+                    .clicked()
+                {
+                    history_events.send(HistoryEvent::Undo);
+                }
+                if ui
+                    .add_enabled(history.0.can_redo(), egui::Button::new("Redo"))
+                    .clicked()
+                {
+                    history_events.send(HistoryEvent::Redo);
+                }
+                if ui.button("Hint").clicked() {
+                    hint.0 = session.0.hint();
+                }
+            });
+            if let Some(h) = &hint.0 {
+                ui.label(format!(
+                    "Hint: {} for {} points (x{} chain)",
+                    h.mv, h.score, h.cascade_depth
+                ));
+            }
+        });
+}
+
+fn show_startup_error_window(mut contexts: EguiContexts, error: Res<StartupErrorResource>) {
+    egui::Window::new("Could not start the game")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(contexts.ctx_mut(), |ui| {
+            ui.label(error.0.to_string());
+        });
+}
@@ -0,0 +1,145 @@
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+
+use crate::{
+    components::{FadingLetter, HoverableTile, LetterTile},
+    events::HistoryEvent,
+    models::{array2d::Int2, history::Snapshot, letter::Letter, letterfield::Letterfield},
+    resources::{
+        GameSessionResource, MoveHistoryResource, ReplayPlaybackResource, RngResource, ThemeAssets,
+        WordMatchesResource,
+    },
+    state::IngameState,
+    systems::setup::create_letter_tile,
+};
+
+pub struct HistorySystemsPlugin;
+impl Plugin for HistorySystemsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            // a replay that is played back is not interrupted by the player:
+            (send_history_events_on_keys, apply_history_events)
+                .chain()
+                .run_if(not(resource_exists::<ReplayPlaybackResource>()))
+                .run_if(resource_exists::<GameSessionResource>()),
+        );
+    }
+}
+
+/// ctrl+z to undo, ctrl+y or ctrl+shift+z to redo.
+fn send_history_events_on_keys(
+    keys: Res<Input<KeyCode>>,
+    mut history_events: EventWriter<HistoryEvent>,
+) {
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    if !ctrl {
+        return;
+    }
+    if keys.just_pressed(KeyCode::Z) {
+        history_events.send(if shift {
+            HistoryEvent::Redo
+        } else {
+            HistoryEvent::Undo
+        });
+    } else if keys.just_pressed(KeyCode::Y) {
+        history_events.send(HistoryEvent::Redo);
+    }
+}
+
+/// restores the letterfield and game state of the history, only between moves.
+fn apply_history_events(
+    mut history_events: EventReader<HistoryEvent>,
+    mut history: ResMut<MoveHistoryResource>,
+    mut session: ResMut<GameSessionResource>,
+    mut rng: ResMut<RngResource>,
+    mut word_matches: ResMut<WordMatchesResource>,
+    ingame_state: Res<State<IngameState>>,
+    mut next_state: ResMut<NextState<IngameState>>,
+    mut tiles: Query<(Entity, &mut LetterTile), Without<FadingLetter>>,
+    mut commands: Commands,
+    theme_assets: Res<ThemeAssets>,
+) {
+    let between_moves = matches!(
+        ingame_state.get(),
+        IngameState::Inspect | IngameState::GameOver
+    );
+    let mut target: Option<Snapshot<_>> = None;
+    for event in history_events.iter() {
+        if !between_moves {
+            continue;
+        }
+        let snapshot = match event {
+            HistoryEvent::Undo => history.0.undo(),
+            HistoryEvent::Redo => history.0.redo(),
+        };
+        if let Some(snapshot) = snapshot {
+            target = Some(snapshot.clone());
+        }
+    }
+    let Some(target) = target else {
+        return;
+    };
+
+    move_tiles_to(
+        &target.letterfield,
+        &mut tiles,
+        &mut commands,
+        &theme_assets,
+        &mut rng,
+    );
+    session.0.restore(target);
+    word_matches.clear();
+    next_state.set(IngameState::Inspect);
+}
+
+/// lets the tiles fly back to their positions in `target`, fades out the tiles that are not in it
+/// and spawns the ones that are missing (e.g. the letters of a match that is undone).
+fn move_tiles_to(
+    target: &Letterfield,
+    tiles: &mut Query<(Entity, &mut LetterTile), Without<FadingLetter>>,
+    commands: &mut Commands,
+    theme_assets: &ThemeAssets,
+    rng: &mut RngResource,
+) {
+    let target_tiles: HashMap<u32, (Int2, Letter)> = target
+        .iter()
+        .map(|(pos, (id, letter))| (id, (pos, letter)))
+        .collect();
+    let mut present: HashSet<u32> = HashSet::new();
+    for (entity, mut letter_tile) in tiles {
+        match target_tiles.get(&letter_tile.id) {
+            Some((pos, _)) => {
+                present.insert(letter_tile.id);
+                // `move_letter_tiles_to_correct_positions` animates the tile there:
+                if letter_tile.pos != *pos {
+                    letter_tile.pos = *pos;
+                }
+            }
+            None => {
+                commands
+                    .entity(entity)
+                    .remove::<HoverableTile>()
+                    .insert(FadingLetter::new(&mut rng.effects));
+            }
+        }
+    }
+    for (id, (pos, letter)) in target_tiles {
+        if present.contains(&id) {
+            continue;
+        }
+        create_letter_tile(
+            id,
+            letter,
+            pos,
+            target,
+            theme_assets,
+            commands,
+            HoverableTile { hovered: false },
+            None,
+        );
+    }
+}
@@ -1,2 +1,8 @@
+pub mod debug_ui;
+pub mod game_over;
+pub mod game_ui;
+pub mod history;
 pub mod input;
+pub mod replay;
+pub mod score_popups;
 pub mod setup;
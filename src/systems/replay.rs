@@ -0,0 +1,93 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{
+    components::LetterTile,
+    models::{
+        array2d::Int2,
+        replay::{ReplayError, ReplayStep},
+    },
+    resources::{GameSessionResource, MoveHistoryResource, ReplayPlaybackResource},
+    state::IngameState,
+};
+
+pub struct ReplaySystemsPlugin;
+impl Plugin for ReplaySystemsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            play_back_replay.run_if(
+                resource_exists::<ReplayPlaybackResource>().and_then(
+                    in_state(IngameState::Inspect).or_else(in_state(IngameState::GameOver)),
+                ),
+            ),
+        );
+    }
+}
+
+/// plays the moves of the replay one after another, like a player would,
+/// and compares the score and letterfield after each move with the replay.
+fn play_back_replay(
+    mut playback: ResMut<ReplayPlaybackResource>,
+    time: Res<Time>,
+    mut next_state: ResMut<NextState<IngameState>>,
+    mut session: ResMut<GameSessionResource>,
+    mut history: ResMut<MoveHistoryResource>,
+    mut tiles: Query<&mut LetterTile>,
+) {
+    let steps = playback.replay.steps.len();
+    if let Some(index) = playback.pending_check.take() {
+        let expected = playback.replay.steps[index];
+        let actual = ReplayStep::new(expected.mv, session.0.score.total, &session.0.letterfield);
+        if actual != expected {
+            println!(
+                "Replay {}",
+                ReplayError::Diverged {
+                    step: index,
+                    expected,
+                    actual,
+                }
+            );
+            playback.divergences += 1;
+        }
+        if index + 1 == steps {
+            println!(
+                "Replay finished with {} diverged moves",
+                playback.divergences
+            );
+        }
+    }
+    if playback.next_step >= steps {
+        return;
+    }
+    if !playback.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    let index = playback.next_step;
+    let mv = playback.replay.steps[index].mv;
+    let before = session.0.snapshot();
+    let outcome = match session.0.apply_move(mv) {
+        Ok(outcome) => outcome,
+        Err(err) => {
+            println!("Replay {}", ReplayError::Move { step: index, err });
+            playback.next_step = steps;
+            return;
+        }
+    };
+    playback.next_step += 1;
+    playback.pending_check = Some(index);
+    let shifted_positions: HashMap<u32, Int2> = outcome
+        .shifted_tiles
+        .iter()
+        .map(|tile| (tile.id, tile.to))
+        .collect();
+    for mut letter_tile in &mut tiles {
+        if let Some(pos) = shifted_positions.get(&letter_tile.id) {
+            letter_tile.pos = *pos;
+        }
+    }
+
+    history.0.begin_move(mv, before);
+    // the session resolves the move, even if it created no word:
+    next_state.set(IngameState::Resolve);
+}
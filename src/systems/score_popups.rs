@@ -0,0 +1,99 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::ScorePopup,
+    events::MatchScoredEvent,
+    models::{corpus::Rarity, letterfield::WordMatchKind, score::MatchScore},
+    resources::{GameSessionResource, ThemeAssets},
+    utils::char_pos_to_world_pos,
+};
+
+/// shows the points, rarity tier and chain of every resolved match above it.
+pub struct ScorePopupSystemsPlugin;
+impl Plugin for ScorePopupSystemsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (spawn_score_popups, animate_score_popups)
+                .run_if(resource_exists::<GameSessionResource>()),
+        );
+    }
+}
+
+/// in seconds:
+const POPUP_DURATION: f32 = 1.5;
+/// in pixels per second:
+const POPUP_RISE_SPEED: f32 = 40.0;
+
+fn spawn_score_popups(
+    mut match_scored: EventReader<MatchScoredEvent>,
+    session: Res<GameSessionResource>,
+    theme_assets: Res<ThemeAssets>,
+    mut commands: Commands,
+) {
+    let (w, h) = session.0.letterfield.dimensions();
+    for MatchScoredEvent(score) in match_scored.iter() {
+        let tiles = &score.word_match.tiles;
+        let center = tiles
+            .iter()
+            .map(|(_, _, pos)| char_pos_to_world_pos(*pos, w, h))
+            .sum::<Vec2>()
+            / tiles.len().max(1) as f32;
+        let style = TextStyle {
+            font_size: theme_assets.tile_text_style.font_size / 2.0,
+            ..theme_assets.tile_text_style.clone()
+        };
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(popup_text(score), style),
+                transform: Transform::from_translation(center.extend(20.0)),
+                ..default()
+            },
+            ScorePopup::default(),
+        ));
+    }
+}
+
+/// lets the popups rise and fade out, then removes them.
+fn animate_score_popups(
+    mut popups: Query<(Entity, &mut ScorePopup, &mut Transform, &mut Text)>,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    for (entity, mut popup, mut transform, mut text) in &mut popups {
+        popup.time += time.delta_seconds();
+        if popup.time >= POPUP_DURATION {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        transform.translation.y += POPUP_RISE_SPEED * time.delta_seconds();
+        let alpha = 1.0 - popup.time / POPUP_DURATION;
+        for section in &mut text.sections {
+            section.style.color.set_a(alpha);
+        }
+    }
+}
+
+/// e.g. "CAT +30", "rare x3" and "chain x2" below each other.
+fn popup_text(score: &MatchScore) -> String {
+    let mut lines = vec![];
+    match &score.word_match.kind {
+        WordMatchKind::Word(word, rarity) => {
+            lines.push(format!("{} +{}", word.to_uppercase(), score.points));
+            let tier = match rarity {
+                Rarity::Common => "common",
+                Rarity::Uncommon => "uncommon",
+                Rarity::Rare => "rare",
+            };
+            lines.push(format!("{tier} x{}", score.rarity_multiplier));
+        }
+        WordMatchKind::SameLetterRow(letter) => {
+            let row = letter.to_string().repeat(score.word_match.tiles.len());
+            lines.push(format!("{row} +{}", score.points));
+        }
+    }
+    if score.cascade_depth > 1 {
+        lines.push(format!("chain x{}", score.cascade_multiplier));
+    }
+    lines.join("\n")
+}
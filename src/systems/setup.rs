@@ -3,16 +3,18 @@ use bevy::prelude::*;
 use crate::{
     components::{EmptyComponent, HoverableTile, LetterTile},
     constants::{TILE_GAP_FACTOR, TILE_SIZE, TILE_SPRITE_SIZE},
-    models::{array2d::Int2, letterfield::Letterfield},
-    resources::{FontAssets, LetterfieldResource},
+    models::{array2d::Int2, letter::Letter, letterfield::Letterfield},
+    resources::{GameSessionResource, ThemeAssets},
     utils::char_pos_to_world_pos,
 };
 
 pub struct SetupSystemsPlugin;
 impl Plugin for SetupSystemsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, init_camera)
-            .add_systems(Startup, setup_letter_field_tiles);
+        app.add_systems(Startup, init_camera).add_systems(
+            Startup,
+            setup_letter_field_tiles.run_if(resource_exists::<GameSessionResource>()),
+        );
     }
 }
 
@@ -23,20 +25,26 @@ fn init_camera(mut commands: Commands) {
 
 fn setup_letter_field_tiles(
     mut commands: Commands,
-    letterfield: Res<LetterfieldResource>,
-    font_assets: Res<FontAssets>,
-    asset_server: Res<AssetServer>,
+    session: Res<GameSessionResource>,
+    theme_assets: Res<ThemeAssets>,
+) {
+    spawn_letter_field_tiles(&session.0.letterfield, &theme_assets, &mut commands);
+}
+
+/// a tile for every letter of the letterfield, at its position.
+pub fn spawn_letter_field_tiles(
+    letterfield: &Letterfield,
+    theme_assets: &ThemeAssets,
+    commands: &mut Commands,
 ) {
-    // create lettertiles
-    for (pos, (id, character)) in letterfield.0.iter() {
+    for (pos, (id, character)) in letterfield.iter() {
         create_letter_tile(
             id,
             character,
             pos,
-            &letterfield.0,
-            &font_assets,
-            &asset_server,
-            &mut commands,
+            letterfield,
+            theme_assets,
+            commands,
             HoverableTile { hovered: false },
             None,
         );
@@ -45,11 +53,10 @@ fn setup_letter_field_tiles(
 
 pub fn create_letter_tile(
     id: u32,
-    character: char,
+    character: Letter,
     pos: Int2,
     letterfield: &Letterfield,
-    font_assets: &FontAssets,
-    asset_server: &AssetServer,
+    theme_assets: &ThemeAssets,
     commands: &mut Commands,
     additional: impl Bundle,
     custom_position: Option<Vec2>,
@@ -81,14 +88,19 @@ pub fn create_letter_tile(
             },
             ..default()
         },
-        texture: asset_server.load("tile.png"),
+        texture: theme_assets.tile_texture.clone(),
         ..default()
     };
 
     // child 2: the text
-
+    // multi-letter tiles like QU get a smaller font, such that all letters fit on the tile:
+    let letter_count = character.chars().count() as f32;
+    let text_style = TextStyle {
+        font_size: theme_assets.tile_text_style.font_size / letter_count.sqrt(),
+        ..theme_assets.tile_text_style.clone()
+    };
     let tile_text = Text2dBundle {
-        text: Text::from_section(character.to_string(), font_assets.tile_text_style.clone()),
+        text: Text::from_section(character.to_string(), text_style),
         transform: Transform {
             translation: Vec3 {
                 x: 0.,